@@ -0,0 +1,424 @@
+use {
+    super::{
+        evaluate, Bound, Engine, SearchLimits, TranspositionTable, TtEntry, TtStats, WIN_SCORE,
+    },
+    crate::model::{Game, GameState, Moves},
+    std::{
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        thread,
+        time::{Duration, Instant},
+    },
+};
+
+/// Outcome of a search, see [`AlphaBeta::search`].
+#[derive(Clone, Copy, Debug)]
+pub struct SearchResult {
+    /// Best move found for the current player.
+    pub best: Moves,
+    /// Score of the best move, from the current player's point of view.
+    pub score: i32,
+    /// Depth, in plies, of the last completed iteration, 0 if the search was stopped during
+    /// the first one.
+    pub depth: u32,
+    /// Number of positions visited.
+    pub nodes: u64,
+}
+
+/// Negamax search with alpha-beta pruning and iterative deepening.
+///
+/// The search goes one ply deeper at each iteration until it reaches one of its
+/// [`SearchLimits`] or is stopped, see [`AlphaBeta::with_stop`]. The result of the last
+/// completed iteration is then kept, or the best move found so far if the first one didn't
+/// complete.
+/// Results are cached in a [`TranspositionTable`], kept from one search to the next and
+/// shared by the clones of the engine.
+///
+/// With more than one thread, the search is parallelized the Lazy SMP way: helper threads
+/// run the same search alongside the main one, starting from other moves and depths, and
+/// fill the shared table with results the main thread picks up. Only the main thread's
+/// result is kept, the helpers being stopped as soon as it's done. With a single thread,
+/// the search is deterministic.
+#[derive(Clone, Debug)]
+pub struct AlphaBeta {
+    limits: SearchLimits,
+    threads: usize,
+    deadline: Option<Instant>,
+    nodes: u64,
+    aborted: bool,
+    tt: Arc<TranspositionTable>,
+    stop: Option<Arc<AtomicBool>>,
+    /// Set once the main thread is done, for the helper threads.
+    done: Option<Arc<AtomicBool>>,
+}
+impl AlphaBeta {
+    /// How many nodes are visited between two checks of the clock and the stop flags.
+    const CLOCK_CHECK_PERIOD: u64 = 1024;
+    /// Depth searched to when not limited, enough to reach the end of any game.
+    const MAX_DEPTH: u32 = 32;
+    /// Default number of entries of the transposition table.
+    const TT_CAPACITY: usize = 1 << 18;
+    /// Scores above this, in absolute value, are wins: a game lasts at most 32 plies.
+    const WIN_THRESHOLD: i32 = WIN_SCORE - 64;
+
+    pub fn new(max_depth: u32) -> Self {
+        Self {
+            limits: SearchLimits::depth(max_depth.max(1)),
+            threads: 1,
+            deadline: None,
+            nodes: 0,
+            aborted: false,
+            tt: Arc::new(TranspositionTable::new(Self::TT_CAPACITY)),
+            stop: None,
+            done: None,
+        }
+    }
+    /// Replaces the limits of the search, including the maximum depth given to
+    /// [`AlphaBeta::new`].
+    pub fn with_limits(mut self, limits: SearchLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+    /// Stops the search once `time_limit` has elapsed, see [`SearchLimits::movetime`].
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.limits.movetime = Some(time_limit);
+        self
+    }
+    /// Stops the search as soon as `stop` is set, from any thread. The flag is never
+    /// cleared by the engine.
+    pub fn with_stop(mut self, stop: Arc<AtomicBool>) -> Self {
+        self.set_stop(stop);
+        self
+    }
+    /// Replaces the flag stopping the search, see [`AlphaBeta::with_stop`], for an engine
+    /// kept from one search to the next.
+    pub fn set_stop(&mut self, stop: Arc<AtomicBool>) {
+        self.stop = Some(stop);
+    }
+    /// Sets the number of entries of the transposition table, see
+    /// [`TranspositionTable::new`].
+    pub fn with_tt_capacity(mut self, capacity: usize) -> Self {
+        self.tt = Arc::new(TranspositionTable::new(capacity));
+        self
+    }
+    /// Sets the number of threads searching in parallel, 1 by default.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+    /// Statistics of the transposition table, over all the searches so far.
+    pub fn tt_stats(&self) -> TtStats {
+        self.tt.stats()
+    }
+
+    /// Converts a win score counted from the root to one counted from the node `ply` plies
+    /// away, so that it can be reused wherever the position is met again.
+    fn score_to_tt(score: i32, ply: u32) -> i32 {
+        match score {
+            s if s > Self::WIN_THRESHOLD => s + ply as i32,
+            s if s < -Self::WIN_THRESHOLD => s - ply as i32,
+            s => s,
+        }
+    }
+    fn score_from_tt(score: i32, ply: u32) -> i32 {
+        match score {
+            s if s > Self::WIN_THRESHOLD => s - ply as i32,
+            s if s < -Self::WIN_THRESHOLD => s + ply as i32,
+            s => s,
+        }
+    }
+
+    fn should_stop(&mut self) -> bool {
+        let is_set = |flag: &Option<Arc<AtomicBool>>| {
+            flag.as_ref()
+                .is_some_and(|flag| flag.load(Ordering::Relaxed))
+        };
+        if self.aborted {
+            return true;
+        }
+        self.aborted = self.limits.nodes.is_some_and(|nodes| self.nodes >= nodes)
+            || self.nodes.is_multiple_of(Self::CLOCK_CHECK_PERIOD)
+                && (self.deadline.is_some_and(|d| Instant::now() >= d)
+                    || is_set(&self.stop)
+                    || is_set(&self.done));
+        self.aborted
+    }
+    /// Score of the move just played on `game` by the player who was to move, `ply` plies
+    /// away from the root.
+    fn score_played(
+        &mut self,
+        game: &mut Game,
+        depth: u32,
+        alpha: i32,
+        beta: i32,
+        ply: u32,
+    ) -> i32 {
+        match game.state() {
+            GameState::Started => -self.negamax(game, depth - 1, -beta, -alpha, ply + 1),
+            GameState::Draw => 0,
+            GameState::PinkWins | GameState::BlackWins => WIN_SCORE - ply as i32 - 1,
+        }
+    }
+    fn negamax(&mut self, game: &mut Game, depth: u32, mut alpha: i32, beta: i32, ply: u32) -> i32 {
+        if self.should_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+        if depth == 0 {
+            return evaluate(game.board(), game.current_player().color());
+        }
+        let hash = game.board().hash();
+        let entry = self.tt.probe(hash);
+        if let Some(entry) = entry.filter(|entry| entry.depth >= depth) {
+            let score = Self::score_from_tt(entry.score, ply);
+            match entry.bound {
+                Bound::Exact => return score,
+                Bound::Lower if score >= beta => return score,
+                Bound::Upper if score <= alpha => return score,
+                _ => {}
+            }
+        }
+
+        let mut moves = game.legal_moves().collect::<Vec<_>>();
+        // Search the best move of the previous search of the position first
+        if let Some(i) = entry
+            .and_then(|entry| entry.best)
+            .and_then(|best| moves.iter().position(|m| *m == best))
+        {
+            moves[..=i].rotate_right(1);
+        }
+        let original_alpha = alpha;
+        let mut best = -WIN_SCORE;
+        let mut best_move = None;
+        for moves in moves {
+            let undo = game.make(moves).expect("legal moves can be played");
+            let score = self.score_played(game, depth, alpha, beta, ply);
+            game.unmake(undo);
+            if self.aborted {
+                return 0;
+            }
+            if score > best {
+                best = score;
+                best_move = Some(moves);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        let bound = if best <= original_alpha {
+            Bound::Upper
+        } else if best >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.tt.store(
+            hash,
+            TtEntry {
+                depth,
+                bound,
+                score: Self::score_to_tt(best, ply),
+                best: best_move,
+            },
+        );
+        best
+    }
+    fn search_root(&mut self, game: &mut Game, depth: u32, moves: &mut [Moves]) -> Option<i32> {
+        let mut alpha = -WIN_SCORE;
+        let mut best = 0;
+        for (i, m) in moves.iter().enumerate() {
+            let undo = game.make(*m).expect("legal moves can be played");
+            let score = self.score_played(game, depth, alpha, WIN_SCORE, 0);
+            game.unmake(undo);
+            if self.aborted {
+                // Keep the best move searched so far first, in case there is no better
+                moves[..=best].rotate_right(1);
+                return None;
+            }
+            if score > alpha {
+                alpha = score;
+                best = i;
+            }
+        }
+        // Search the best move first at the next iteration
+        moves[..=best].rotate_right(1);
+        Some(alpha)
+    }
+
+    /// Runs the iterative deepening on the thread of index `thread`, 0 being the main one.
+    /// The helper threads start from another root move, and every other one a ply deeper.
+    fn iterate(&mut self, game: &Game, thread: usize) -> Option<SearchResult> {
+        let mut game = game.clone();
+        let mut moves = game.legal_moves().collect::<Vec<_>>();
+        let len = moves.len();
+        if len > 0 {
+            moves.rotate_left(thread % len);
+        }
+        let mut result = SearchResult {
+            best: *moves.first()?,
+            score: 0,
+            depth: 0,
+            nodes: 0,
+        };
+        self.nodes = 0;
+        self.aborted = false;
+        let start = Instant::now();
+        let budget = self.limits.time_budget(&game);
+        self.deadline = budget.map(|budget| start + budget);
+
+        let max_depth = self.limits.depth.unwrap_or(Self::MAX_DEPTH);
+        for depth in 1 + thread as u32 % 2..=max_depth {
+            // The next iteration is unlikely to complete past half the time
+            if result.depth > 0 && budget.is_some_and(|budget| start.elapsed() > budget / 2) {
+                break;
+            }
+            let Some(score) = self.search_root(&mut game, depth, &mut moves) else {
+                if result.depth == 0 {
+                    result.best = moves[0];
+                }
+                break;
+            };
+            result.best = moves[0];
+            result.score = score;
+            result.depth = depth;
+            if score.abs() > Self::WIN_THRESHOLD {
+                break;
+            }
+        }
+        result.nodes = self.nodes;
+        Some(result)
+    }
+
+    /// Searches the best move for the current player, or `None` if the game is over.
+    ///
+    /// With several threads, the node limit only applies to the main one.
+    pub fn search(&mut self, game: &Game) -> Option<SearchResult> {
+        self.tt.new_search();
+        if self.threads == 1 {
+            return self.iterate(game, 0);
+        }
+        let done = Arc::new(AtomicBool::new(false));
+        thread::scope(|scope| {
+            let helpers = (1..self.threads)
+                .map(|thread| {
+                    let mut helper = Self {
+                        limits: SearchLimits {
+                            depth: self.limits.depth,
+                            ..SearchLimits::default()
+                        },
+                        done: Some(done.clone()),
+                        ..self.clone()
+                    };
+                    scope.spawn(move || {
+                        helper.iterate(game, thread);
+                        helper.nodes
+                    })
+                })
+                .collect::<Vec<_>>();
+            let result = self.iterate(game, 0);
+            done.store(true, Ordering::Relaxed);
+            let nodes = helpers
+                .into_iter()
+                .map(|helper| helper.join().expect("helper threads don't panic"))
+                .sum::<u64>();
+            result.map(|result| SearchResult {
+                nodes: result.nodes + nodes,
+                ..result
+            })
+        })
+    }
+}
+impl Engine for AlphaBeta {
+    fn best_move(&mut self, game: &Game) -> Option<Moves> {
+        self.search(game).map(|result| result.best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_immediate_win() {
+        let mut game = Game::from_fen("POPOPO3/6/2TX3/3TO2/6/BXBXBX3 8,5/5,8 p").unwrap();
+        let result = AlphaBeta::new(3).search(&game).unwrap();
+        assert_eq!(result.score, WIN_SCORE - 1);
+        game.play(result.best).unwrap();
+        assert_eq!(game.state(), &GameState::PinkWins);
+    }
+
+    #[test]
+    fn respects_time_limit() {
+        let game = Game::default();
+        let start = Instant::now();
+        let result = AlphaBeta::new(20)
+            .with_time_limit(Duration::from_millis(200))
+            .search(&game)
+            .unwrap();
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(result.depth >= 1);
+        assert!(game.legal_moves().any(|m| m == result.best));
+    }
+
+    #[test]
+    fn respects_node_limit_and_stop() {
+        let game = Game::default();
+        let result = AlphaBeta::new(20)
+            .with_limits(SearchLimits::nodes(500))
+            .search(&game)
+            .unwrap();
+        assert_eq!(result.nodes, 500);
+        assert!(game.legal_moves().any(|m| m == result.best));
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut engine = AlphaBeta::new(1)
+            .with_limits(SearchLimits::default())
+            .with_stop(stop.clone());
+        let searching = thread::spawn(move || engine.search(&Game::default()));
+        thread::sleep(Duration::from_millis(100));
+        stop.store(true, Ordering::Relaxed);
+        let result = searching.join().unwrap().unwrap();
+        assert!(game.legal_moves().any(|m| m == result.best));
+    }
+
+    #[test]
+    fn transposition_table_saves_nodes() {
+        let game =
+            Game::from_fen("1BOPXPO2/BOTO2PX1/3BXBO1/5BX/1PXBX3/1POTX1PO1 5,5/5,5 p").unwrap();
+        let mut engine = AlphaBeta::new(3);
+        let first = engine.search(&game).unwrap();
+        assert!(engine.tt_stats().hits > 0);
+        let second = engine.search(&game).unwrap();
+        assert_eq!(second.score, first.score);
+        assert!(second.nodes < first.nodes);
+
+        let uncached = AlphaBeta::new(3).with_tt_capacity(1).search(&game).unwrap();
+        assert_eq!(uncached.score, first.score);
+    }
+
+    #[test]
+    fn parallel_search() {
+        let game =
+            Game::from_fen("1BOPXPO2/BOTO2PX1/3BXBO1/5BX/1PXBX3/1POTX1PO1 5,5/5,5 p").unwrap();
+        let single = AlphaBeta::new(4).search(&game).unwrap();
+        let again = AlphaBeta::new(4).search(&game).unwrap();
+        assert_eq!(
+            (again.best, again.score, again.nodes),
+            (single.best, single.score, single.nodes)
+        );
+
+        let parallel = AlphaBeta::new(4).with_threads(4).search(&game).unwrap();
+        assert_eq!(parallel.depth, 4);
+        assert!(parallel.nodes > 0);
+        assert!(game.legal_moves().any(|m| m == parallel.best));
+
+        let mut game = Game::from_fen("POPOPO3/6/2TX3/3TO2/6/BXBXBX3 8,5/5,8 p").unwrap();
+        let result = AlphaBeta::new(3).with_threads(4).search(&game).unwrap();
+        game.play(result.best).unwrap();
+        assert_eq!(game.state(), &GameState::PinkWins);
+    }
+}
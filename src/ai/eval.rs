@@ -0,0 +1,44 @@
+use crate::model::{Board, Color, Position, Square, Symbol};
+
+/// Score of a won position, decreased by the number of plies needed to reach it so that
+/// faster wins are preferred.
+pub const WIN_SCORE: i32 = 100_000;
+
+/// Value of a line of four squares holding 0, 1, 2 or 3 pieces that can still all share
+/// the same color or the same symbol.
+const LINE_WEIGHTS: [i32; 4] = [0, 1, 8, 64];
+
+/// Heuristic value of `board` for the player of the given color, to move.
+///
+/// Each group of four squares checked by [`Board::wins`] is scored by how many pieces it
+/// already holds as long as it can still be completed: open lines of the player's color
+/// count for them and those of the opponent against them. Lines of a single symbol can be
+/// completed by either player, so they are worth half as much, to the side to move.
+pub fn evaluate(board: &Board, color: Color) -> i32 {
+    let mut score = 0;
+    for four in Position::four_latteral_groups() {
+        let mut colors = [0; 2];
+        let mut symbols = [0; 2];
+        for pos in four {
+            if let &Square::Piece(symbol, piece_color) = board.get(pos) {
+                colors[piece_color as usize] += 1;
+                symbols[symbol as usize] += 1;
+            }
+        }
+        let mine = colors[color as usize];
+        let theirs = colors[color.opposite() as usize];
+        if theirs == 0 {
+            score += LINE_WEIGHTS[mine.min(3)];
+        }
+        if mine == 0 {
+            score -= LINE_WEIGHTS[theirs.min(3)];
+        }
+        for symbol in [Symbol::X, Symbol::O] {
+            let same = symbols[symbol as usize];
+            if same == mine + theirs {
+                score += LINE_WEIGHTS[same.min(3)] / 2;
+            }
+        }
+    }
+    score
+}
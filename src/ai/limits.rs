@@ -0,0 +1,94 @@
+use {
+    crate::model::{Game, Symbol},
+    std::time::Duration,
+};
+
+/// Time left to a player to finish the game, along with the time added after each move.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Clock {
+    pub remaining: Duration,
+    pub increment: Duration,
+}
+
+/// When a search has to stop, see [`super::AlphaBeta::with_limits`].
+///
+/// The limits can be combined, the search stopping at the first one reached. Without any,
+/// it goes on until the game is solved or the search is stopped from another thread.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SearchLimits {
+    /// Maximum depth, in plies.
+    pub depth: Option<u32>,
+    /// Maximum number of positions visited.
+    pub nodes: Option<u64>,
+    /// Time to spend on the move.
+    pub movetime: Option<Duration>,
+    /// Clock of the player to move, a share of which is spent on the move.
+    pub clock: Option<Clock>,
+}
+impl SearchLimits {
+    pub fn depth(depth: u32) -> Self {
+        Self {
+            depth: Some(depth),
+            ..Self::default()
+        }
+    }
+    pub fn nodes(nodes: u64) -> Self {
+        Self {
+            nodes: Some(nodes),
+            ..Self::default()
+        }
+    }
+    pub fn movetime(movetime: Duration) -> Self {
+        Self {
+            movetime: Some(movetime),
+            ..Self::default()
+        }
+    }
+    pub fn clock(remaining: Duration, increment: Duration) -> Self {
+        Self {
+            clock: Some(Clock {
+                remaining,
+                increment,
+            }),
+            ..Self::default()
+        }
+    }
+
+    /// Time to spend on the move of the current player of `game`, if limited.
+    ///
+    /// The clock is split evenly between the moves the player has left to play, one per
+    /// piece, plus the increment, never using more than 4/5 of the remaining time.
+    pub fn time_budget(&self, game: &Game) -> Option<Duration> {
+        let from_clock = self.clock.map(|clock| {
+            let color = game.current_player().color();
+            let pieces = game.board().pieces();
+            let moves_left = pieces.get(Symbol::X, color) + pieces.get(Symbol::O, color);
+            let share = clock.remaining / moves_left.max(1) as u32 + clock.increment;
+            share.min(clock.remaining * 4 / 5)
+        });
+        match (self.movetime, from_clock) {
+            (Some(movetime), Some(from_clock)) => Some(movetime.min(from_clock)),
+            (movetime, from_clock) => movetime.or(from_clock),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_budget() {
+        let game = Game::default();
+        let clock = SearchLimits::clock(Duration::from_secs(16), Duration::from_millis(100));
+        assert_eq!(clock.time_budget(&game), Some(Duration::from_millis(1100)));
+        let limits = SearchLimits {
+            movetime: Some(Duration::from_millis(300)),
+            ..clock
+        };
+        assert_eq!(limits.time_budget(&game), Some(Duration::from_millis(300)));
+        let short = SearchLimits::clock(Duration::from_millis(100), Duration::from_secs(1));
+        assert_eq!(short.time_budget(&game), Some(Duration::from_millis(80)));
+        assert_eq!(SearchLimits::depth(3).time_budget(&game), None);
+    }
+}
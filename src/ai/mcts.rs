@@ -0,0 +1,233 @@
+use {
+    super::Engine,
+    crate::model::{Color, Game, GameState, GameUndo, Moves},
+    std::time::{Duration, Instant},
+};
+
+/// Small xorshift generator, good enough to pick random moves in playouts.
+#[derive(Clone, Debug)]
+struct Rng(u64);
+impl Rng {
+    fn next(&mut self) -> u64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        x
+    }
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+}
+
+#[derive(Debug)]
+struct Node {
+    /// Move leading to this node, `None` for the root.
+    moves: Option<Moves>,
+    /// Color of the player who played `moves`.
+    color: Color,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Moves>,
+    visits: u32,
+    /// Sum of the playout results for `color`: 1 for a win, 0.5 for a draw.
+    reward: f64,
+}
+impl Node {
+    fn new(game: &Game, moves: Option<Moves>, color: Color, parent: Option<usize>) -> Self {
+        Self {
+            moves,
+            color,
+            parent,
+            children: Vec::new(),
+            untried: game.legal_moves().collect(),
+            visits: 0,
+            reward: 0.0,
+        }
+    }
+    fn uct(&self, parent_visits: u32, exploration: f64) -> f64 {
+        let visits = self.visits as f64;
+        self.reward / visits + exploration * ((parent_visits as f64).ln() / visits).sqrt()
+    }
+}
+
+/// Outcome of a search, see [`Mcts::search`].
+#[derive(Clone, Copy, Debug)]
+pub struct MctsResult {
+    /// Most visited move of the current player.
+    pub best: Moves,
+    /// Ratio of the playouts through `best` won by the current player, draws counting half.
+    pub win_rate: f64,
+    /// Number of iterations run.
+    pub iterations: u32,
+}
+
+/// Monte Carlo Tree Search using the UCT selection rule.
+///
+/// Each iteration walks down the tree to a node with untried moves, expands one of them
+/// and finishes the game with random moves. The search stops after the given number of
+/// iterations or when the time limit elapses, whichever comes first.
+#[derive(Clone, Debug)]
+pub struct Mcts {
+    iterations: u32,
+    time_limit: Option<Duration>,
+    exploration: f64,
+    guided: bool,
+    rng: Rng,
+}
+impl Mcts {
+    pub fn new(iterations: u32) -> Self {
+        Self {
+            iterations: iterations.max(1),
+            time_limit: None,
+            exploration: core::f64::consts::SQRT_2,
+            guided: true,
+            rng: Rng(0x9E37_79B9_7F4A_7C15),
+        }
+    }
+    /// Stops the search once `time_limit` has elapsed, even if all the iterations aren't
+    /// run. At least one iteration is always run.
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+    /// Sets the exploration constant of UCT, `sqrt(2)` by default.
+    pub fn with_exploration(mut self, exploration: f64) -> Self {
+        self.exploration = exploration;
+        self
+    }
+    /// When guided (the default), playouts always take an immediately winning move if
+    /// there is one instead of a random move.
+    pub fn with_guided_playouts(mut self, guided: bool) -> Self {
+        self.guided = guided;
+        self
+    }
+    /// Seeds the random generator used by the playouts.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = Rng(seed.max(1));
+        self
+    }
+
+    fn winning_move(game: &mut Game, moves: &[Moves]) -> Option<Moves> {
+        moves.iter().copied().find(|m| {
+            let undo = game.make(*m).expect("legal moves can be played");
+            let wins = matches!(game.state(), GameState::PinkWins | GameState::BlackWins);
+            game.unmake(undo);
+            wins
+        })
+    }
+    /// Plays random moves until the end of the game, pushing them on `played`, and returns
+    /// the final state.
+    fn playout(&mut self, game: &mut Game, played: &mut Vec<GameUndo>) -> GameState {
+        while !game.state().is_over() {
+            let moves = game.legal_moves().collect::<Vec<_>>();
+            let winning = self
+                .guided
+                .then(|| Self::winning_move(game, &moves))
+                .flatten();
+            let moves = winning.unwrap_or_else(|| moves[self.rng.below(moves.len())]);
+            played.push(game.make(moves).expect("legal moves can be played"));
+        }
+        *game.state()
+    }
+    fn reward(state: GameState, color: Color) -> f64 {
+        match (state, color) {
+            (GameState::PinkWins, Color::Pink) | (GameState::BlackWins, Color::Black) => 1.0,
+            (GameState::Draw | GameState::Started, _) => 0.5,
+            _ => 0.0,
+        }
+    }
+
+    /// Searches the best move for the current player, or `None` if the game is over.
+    pub fn search(&mut self, game: &Game) -> Option<MctsResult> {
+        let mut game = game.clone();
+        let root_color = game.current_player().color().opposite();
+        let mut tree = vec![Node::new(&game, None, root_color, None)];
+        if tree[0].untried.is_empty() {
+            return None;
+        }
+        let deadline = self.time_limit.map(|limit| Instant::now() + limit);
+        let mut played = Vec::new();
+
+        let mut iterations = 0;
+        while iterations < self.iterations
+            && (iterations == 0 || deadline.is_none_or(|d| Instant::now() < d))
+        {
+            iterations += 1;
+            let mut node = 0;
+
+            // Selection
+            while tree[node].untried.is_empty() && !tree[node].children.is_empty() {
+                let parent_visits = tree[node].visits;
+                node = *tree[node]
+                    .children
+                    .iter()
+                    .max_by(|a, b| {
+                        let a = tree[**a].uct(parent_visits, self.exploration);
+                        let b = tree[**b].uct(parent_visits, self.exploration);
+                        a.total_cmp(&b)
+                    })
+                    .expect("the node has children");
+                let moves = tree[node].moves.expect("only the root has no move");
+                played.push(game.make(moves).expect("legal moves can be played"));
+            }
+
+            // Expansion
+            if !tree[node].untried.is_empty() {
+                let untried = &mut tree[node].untried;
+                let moves = untried.swap_remove(self.rng.below(untried.len()));
+                let color = game.current_player().color();
+                played.push(game.make(moves).expect("legal moves can be played"));
+                tree.push(Node::new(&game, Some(moves), color, Some(node)));
+                let child = tree.len() - 1;
+                tree[node].children.push(child);
+                node = child;
+            }
+
+            // Simulation
+            let state = self.playout(&mut game, &mut played);
+            while let Some(undo) = played.pop() {
+                game.unmake(undo);
+            }
+
+            // Backpropagation
+            let mut current = Some(node);
+            while let Some(n) = current {
+                tree[n].visits += 1;
+                tree[n].reward += Self::reward(state, tree[n].color);
+                current = tree[n].parent;
+            }
+        }
+
+        let best = tree[0]
+            .children
+            .iter()
+            .map(|c| &tree[*c])
+            .max_by_key(|c| c.visits)?;
+        Some(MctsResult {
+            best: best.moves?,
+            win_rate: best.reward / best.visits as f64,
+            iterations,
+        })
+    }
+}
+impl Engine for Mcts {
+    fn best_move(&mut self, game: &Game) -> Option<Moves> {
+        self.search(game).map(|result| result.best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_immediate_win() {
+        let mut game = Game::from_fen("POPOPO3/6/2TX3/3TO2/6/BXBXBX3 8,5/5,8 p").unwrap();
+        let result = Mcts::new(300).with_seed(42).search(&game).unwrap();
+        assert_eq!(result.iterations, 300);
+        game.play(result.best).unwrap();
+        assert_eq!(game.state(), &GameState::PinkWins);
+    }
+}
@@ -0,0 +1,25 @@
+//! Computer opponents built on top of the [`crate::model`].
+
+mod alphabeta;
+mod eval;
+mod limits;
+mod mcts;
+mod solver;
+mod tt;
+
+pub use {
+    alphabeta::{AlphaBeta, SearchResult},
+    eval::{evaluate, WIN_SCORE},
+    limits::{Clock, SearchLimits},
+    mcts::{Mcts, MctsResult},
+    solver::{Solution, Solver},
+    tt::{Bound, TranspositionTable, TtEntry, TtStats},
+};
+
+use crate::model::{Game, Moves};
+
+/// Something able to pick a move for the current player of a game.
+pub trait Engine {
+    /// Returns the move chosen for the current player, or `None` if the game is over.
+    fn best_move(&mut self, game: &Game) -> Option<Moves>;
+}
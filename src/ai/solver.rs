@@ -0,0 +1,178 @@
+use {
+    crate::model::{Game, GameState, Moves, Symbol},
+    std::collections::HashMap,
+};
+
+/// Outcome of [`Solver::solve`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Solution {
+    /// The current player wins within `moves` of its moves, whatever the opponent plays.
+    ///
+    /// `line` is the proof: the moves of the player alternating with the opponent's longest
+    /// defence, ending with the winning move.
+    Win { moves: u32, line: Vec<Moves> },
+    /// The current player can't force a win within the number of moves searched. When that
+    /// number covers all of its pieces left, it can't force a win at all.
+    NoForcedWin,
+}
+
+/// Exact search of forced wins for the current player, proving "win in N" or the lack of
+/// one.
+///
+/// The player's moves are searched for one winning whatever the opponent answers, with the
+/// shortest wins looked for first. [`crate::model::Board::threats`] tells at once whether
+/// a player can win in one move, which makes late positions, with few pieces left, quick
+/// to solve exactly.
+#[derive(Clone, Debug)]
+pub struct Solver {
+    max_moves: u32,
+    nodes: u64,
+    /// For each position searched, the number of moves the player to move was proven not to
+    /// be able to force a win within.
+    refuted: HashMap<u64, u32>,
+}
+impl Solver {
+    /// Creates a solver looking for wins within `max_moves` moves of the current player.
+    pub fn new(max_moves: u32) -> Self {
+        Self {
+            max_moves,
+            nodes: 0,
+            refuted: HashMap::new(),
+        }
+    }
+    /// Number of positions visited by the last solve.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Looks for a forced win of the current player of `game`.
+    pub fn solve(&mut self, game: &Game) -> Solution {
+        self.nodes = 0;
+        self.refuted.clear();
+        if game.state().is_over() {
+            return Solution::NoForcedWin;
+        }
+        let mut game = game.clone();
+        let color = game.current_player().color();
+        let pieces = game.board().pieces();
+        let pieces_left = pieces.get(Symbol::X, color) + pieces.get(Symbol::O, color);
+        for moves in 1..=self.max_moves.min(pieces_left as u32) {
+            if let Some(line) = self.attack(&mut game, moves) {
+                return Solution::Win { moves, line };
+            }
+        }
+        Solution::NoForcedWin
+    }
+
+    /// The proof of a win of the player to move within `n` of its moves, if there is one.
+    fn attack(&mut self, game: &mut Game, n: u32) -> Option<Vec<Moves>> {
+        self.nodes += 1;
+        let color = game.current_player().color();
+        if let Some(threat) = game.board().threats(color).first() {
+            let moves = game
+                .legal_moves()
+                .find(|m| m.symbol() == threat.symbol() && m.piece_pos() == threat.pos())
+                .expect("threats can be played");
+            return Some(vec![moves]);
+        }
+        let hash = game.board().hash();
+        if n == 1 || self.refuted.get(&hash).is_some_and(|refuted| *refuted >= n) {
+            return None;
+        }
+
+        for moves in game.legal_moves().collect::<Vec<_>>() {
+            let undo = game.make(moves).expect("legal moves can be played");
+            // Without a threat, the move can't win, and a draw is no win
+            let line = match game.state() {
+                GameState::Started => self.defend(game, n - 1),
+                _ => None,
+            };
+            game.unmake(undo);
+            if let Some(mut line) = line {
+                line.insert(0, moves);
+                return Some(line);
+            }
+        }
+        self.refuted.insert(hash, n);
+        None
+    }
+    /// The proof of a win of the opponent of the player to move within `n` of its moves,
+    /// along the longest defence, if the player can't escape it.
+    fn defend(&mut self, game: &mut Game, n: u32) -> Option<Vec<Moves>> {
+        self.nodes += 1;
+        let color = game.current_player().color();
+        if !game.board().threats(color).is_empty() {
+            return None;
+        }
+
+        let mut longest: Option<(Moves, Vec<Moves>)> = None;
+        for moves in game.legal_moves().collect::<Vec<_>>() {
+            let undo = game.make(moves).expect("legal moves can be played");
+            let line = match game.state() {
+                GameState::Started => self.attack(game, n),
+                _ => None,
+            };
+            game.unmake(undo);
+            let line = line?;
+            if longest
+                .as_ref()
+                .is_none_or(|(_, longest)| line.len() > longest.len())
+            {
+                longest = Some((moves, line));
+            }
+        }
+        let (moves, mut line) = longest?;
+        line.insert(0, moves);
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_immediate_win() {
+        let game = Game::from_fen("POPOPO3/6/2TX3/3TO2/6/BXBXBX3 8,5/5,8 p").unwrap();
+        let Solution::Win { moves: 1, line } = Solver::new(3).solve(&game) else {
+            panic!("Pink wins in one move");
+        };
+        let mut game = game;
+        game.play(line[0]).unwrap();
+        assert_eq!(game.state(), &GameState::PinkWins);
+
+        assert_eq!(
+            Solver::new(2).solve(&Game::default()),
+            Solution::NoForcedWin
+        );
+    }
+
+    #[test]
+    fn proves_win_in_three() {
+        let game =
+            Game::from_fen("PO2PX2/BOTOPXBO1BX/1PO1TXBX1/1BO1PO1PX/BO2BO2/2PO3 5,4/6,3 p").unwrap();
+        assert_eq!(Solver::new(2).solve(&game), Solution::NoForcedWin);
+        let Solution::Win { moves: 3, line } = Solver::new(3).solve(&game) else {
+            panic!("Pink wins in three moves");
+        };
+        assert_eq!(line.len(), 5);
+
+        let mut played = game.clone();
+        for moves in &line {
+            played.play(*moves).unwrap();
+        }
+        assert_eq!(played.state(), &GameState::PinkWins);
+
+        // Whatever Black answers to the first move, Pink still wins in two
+        let mut game = game;
+        game.play(line[0]).unwrap();
+        for defence in game.legal_moves().collect::<Vec<_>>() {
+            let mut game = game.clone();
+            game.play(defence).unwrap();
+            assert!(matches!(
+                Solver::new(2).solve(&game),
+                Solution::Win { moves: 1 | 2, .. }
+            ));
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use {
+    crate::model::{Moves, Position, Symbol},
+    std::sync::atomic::{AtomicU64, AtomicU8, Ordering},
+};
+
+/// How the score of a [`TtEntry`] relates to the real value of the position.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Bound {
+    /// The score is the value of the position.
+    Exact,
+    /// The search failed high: the value is at least the score.
+    Lower,
+    /// The search failed low: the value is at most the score.
+    Upper,
+}
+
+/// Result of the search of a position, stored in a [`TranspositionTable`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TtEntry {
+    /// Depth, in plies, the position was searched to.
+    pub depth: u32,
+    pub bound: Bound,
+    pub score: i32,
+    /// Best move found, if any, to be searched first next time.
+    pub best: Option<Moves>,
+}
+
+/// Probe statistics of a [`TranspositionTable`], since its creation or last clear.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct TtStats {
+    pub probes: u64,
+    pub hits: u64,
+    pub stores: u64,
+    /// Stores that overwrote the entry of another position.
+    pub replacements: u64,
+}
+impl TtStats {
+    /// Ratio of the probes that found an entry, 0 if there was no probe.
+    pub fn hit_rate(&self) -> f64 {
+        if self.probes == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.probes as f64
+        }
+    }
+}
+
+/// An entry packed in a single word, see [`TranspositionTable`]:
+/// - bits 0 to 31: the score;
+/// - bits 32 to 37: the depth;
+/// - bits 38 and 39: the bound, never 0 so that an empty slot can't match a position;
+/// - bits 40 to 59: the best move, if bit 40 is set: its symbol then the indices of the
+///   old totem, new totem and piece squares, 6 bits each;
+/// - bits 60 to 63: the generation of the search that stored it.
+#[derive(Clone, Copy, Debug)]
+struct Packed(u64);
+impl Packed {
+    const MAX_DEPTH: u32 = 63;
+
+    fn new(entry: TtEntry, generation: u8) -> Self {
+        let bound = match entry.bound {
+            Bound::Exact => 1,
+            Bound::Lower => 2,
+            Bound::Upper => 3,
+        };
+        let square = |pos: Position| (pos.x() + pos.y() * 6) as u64;
+        let best = entry.best.map_or(0, |m| {
+            1 | (m.symbol() as u64) << 1
+                | square(m.totem_old_pos()) << 2
+                | square(m.totem_new_pos()) << 8
+                | square(m.piece_pos()) << 14
+        });
+        Self(
+            entry.score as u32 as u64
+                | (entry.depth.min(Self::MAX_DEPTH) as u64) << 32
+                | bound << 38
+                | best << 40
+                | ((generation & 0xF) as u64) << 60,
+        )
+    }
+    fn generation(self) -> u8 {
+        (self.0 >> 60) as u8
+    }
+    fn entry(self) -> Option<TtEntry> {
+        let bound = match (self.0 >> 38) & 3 {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+        let best = (self.0 >> 40) & 0xF_FFFF;
+        let square = |shift: u32| {
+            let i = (best >> shift) & 0x3F;
+            Position::new((i % 6) as u8, (i / 6) as u8).ok()
+        };
+        let best = if best & 1 == 0 {
+            None
+        } else {
+            let symbol = if best & 2 == 0 { Symbol::X } else { Symbol::O };
+            Some(Moves::new(symbol, square(2)?, square(8)?, square(14)?))
+        };
+        Some(TtEntry {
+            depth: ((self.0 >> 32) & 0x3F) as u32,
+            bound,
+            score: self.0 as u32 as i32,
+            best,
+        })
+    }
+}
+
+/// Fixed-size cache of search results keyed by [`crate::model::Board::hash`], which can
+/// be shared between threads without locking.
+///
+/// Each hash maps to a single slot. A store replaces the slot if it is empty, holds the
+/// same position, was written by an older search (see [`TranspositionTable::new_search`])
+/// or was searched less deeply than the new entry.
+///
+/// A slot is made of two words: the packed entry and the key XORed with it. Should two
+/// threads write the same slot at once, the words may come from different stores, and the
+/// key read back then doesn't match: the torn slot is seen as empty.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    /// Pairs of words, the key XORed with the entry then the entry.
+    slots: Box<[AtomicU64]>,
+    generation: AtomicU8,
+    probes: AtomicU64,
+    hits: AtomicU64,
+    stores: AtomicU64,
+    replacements: AtomicU64,
+}
+impl TranspositionTable {
+    /// Creates a table holding `capacity` entries, rounded down to a power of two. Depths
+    /// above 63 plies are stored as 63.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = match capacity {
+            0 => 1,
+            n => 1 << n.ilog2(),
+        };
+        Self {
+            slots: (0..capacity * 2).map(|_| AtomicU64::new(0)).collect(),
+            generation: AtomicU8::new(0),
+            probes: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            stores: AtomicU64::new(0),
+            replacements: AtomicU64::new(0),
+        }
+    }
+    pub fn capacity(&self) -> usize {
+        self.slots.len() / 2
+    }
+    pub fn stats(&self) -> TtStats {
+        TtStats {
+            probes: self.probes.load(Ordering::Relaxed),
+            hits: self.hits.load(Ordering::Relaxed),
+            stores: self.stores.load(Ordering::Relaxed),
+            replacements: self.replacements.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Index of the first word of the slot of `key`.
+    fn index(&self, key: u64) -> usize {
+        (key as usize & (self.capacity() - 1)) * 2
+    }
+    /// Reads the slot of `key`: the key it holds and its entry.
+    fn read(&self, key: u64) -> (u64, Packed) {
+        let index = self.index(key);
+        let data = self.slots[index + 1].load(Ordering::Relaxed);
+        (
+            self.slots[index].load(Ordering::Relaxed) ^ data,
+            Packed(data),
+        )
+    }
+
+    /// Returns the entry stored for the position of hash `key`.
+    pub fn probe(&self, key: u64) -> Option<TtEntry> {
+        self.probes.fetch_add(1, Ordering::Relaxed);
+        let (stored, packed) = self.read(key);
+        let entry = packed.entry().filter(|_| stored == key)?;
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        Some(entry)
+    }
+    /// Stores `entry` for the position of hash `key`, unless the slot holds a more valuable
+    /// entry.
+    pub fn store(&self, key: u64, entry: TtEntry) {
+        let generation = self.generation.load(Ordering::Relaxed) & 0xF;
+        let (stored, packed) = self.read(key);
+        if let Some(old) = packed.entry().filter(|_| stored != key) {
+            if packed.generation() == generation && old.depth > entry.depth {
+                return;
+            }
+            self.replacements.fetch_add(1, Ordering::Relaxed);
+        }
+        self.stores.fetch_add(1, Ordering::Relaxed);
+        let index = self.index(key);
+        let data = Packed::new(entry, generation).0;
+        self.slots[index].store(key ^ data, Ordering::Relaxed);
+        self.slots[index + 1].store(data, Ordering::Relaxed);
+    }
+
+    /// Marks the entries stored so far as coming from a previous search, making them the
+    /// first to be replaced. They can still be probed.
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+    /// Removes all the entries and resets the statistics.
+    pub fn clear(&self) {
+        for word in &self.slots {
+            word.store(0, Ordering::Relaxed);
+        }
+        for counter in [&self.probes, &self.hits, &self.stores, &self.replacements] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::model::Game};
+
+    #[test]
+    fn replacement_scheme() {
+        let tt = TranspositionTable::new(100);
+        assert_eq!(tt.capacity(), 64);
+        let best = Game::default().legal_moves().next();
+        let entry = |depth| TtEntry {
+            depth,
+            bound: Bound::Exact,
+            score: -(depth as i32),
+            best,
+        };
+
+        tt.store(3, entry(4));
+        assert_eq!(tt.probe(3), Some(entry(4)));
+        assert_eq!(tt.probe(3 + 64), None);
+        // A shallower entry of another position doesn't replace a deeper one...
+        tt.store(3 + 64, entry(2));
+        assert_eq!(tt.probe(3), Some(entry(4)));
+        // ...unless it comes from a newer search
+        tt.new_search();
+        tt.store(3 + 64, entry(2));
+        assert_eq!(tt.probe(3), None);
+        assert_eq!(tt.probe(3 + 64), Some(entry(2)));
+        // The same position is always updated
+        tt.store(3 + 64, entry(1));
+        assert_eq!(tt.probe(3 + 64), Some(entry(1)));
+
+        let stats = tt.stats();
+        assert_eq!((stats.probes, stats.hits), (6, 4));
+        assert_eq!((stats.stores, stats.replacements), (3, 1));
+        tt.clear();
+        assert_eq!(tt.probe(3 + 64), None);
+        assert_eq!(tt.stats().hit_rate(), 0.0);
+    }
+}
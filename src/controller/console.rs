@@ -1,60 +1,141 @@
 use {
-    crate::{
-        model::{Game, Moves, MovesBuilderError, Symbol},
-        view::View,
+    super::Options,
+    crate::view::View,
+    oxono::{
+        ai::{AlphaBeta, Engine},
+        model::{Color, Game, Moves, MovesBuilderError, Position, Symbol},
     },
     std::io::stdin,
 };
 
+const DEFAULT_RECORD_PATH: &str = "oxono.game";
+
+enum Command {
+    Play(Moves),
+    Undo,
+    Redo,
+    Save(String),
+    Load(String),
+    /// The input is closed.
+    Quit,
+}
+
 pub struct Controller {
     game: Game,
     view: View,
+    computer: Option<Color>,
+    engine: AlphaBeta,
 }
 
 impl Controller {
-    pub fn new() -> Self {
+    pub fn new(options: Options) -> Self {
         Self {
             game: Game::default(),
             view: View,
+            computer: options.computer,
+            engine: options.engine(),
+        }
+    }
+
+    fn is_computer_turn(&self) -> bool {
+        !self.game.state().is_over() && self.computer == Some(self.game.current_player().color())
+    }
+
+    /// Reads a line of the standard input, `None` once it's closed.
+    fn read_line() -> Option<String> {
+        let mut line = String::new();
+        match stdin().read_line(&mut line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(line),
         }
     }
 
-    fn prompt_moves(&mut self) -> Result<Moves, MovesBuilderError> {
+    fn prompt_moves(&mut self) -> Result<Command, MovesBuilderError> {
         let builder = self.game.moves_builder();
 
         self.view.display_prompt_symbol();
-        let builder = match stdin().lines().next().unwrap().as_deref() {
-            Ok("O" | "o") => builder.symbol(Symbol::O)?,
-            Ok("X" | "x") => builder.symbol(Symbol::X)?,
-            _ => panic!("Input/Output error"),
+        let Some(line) = Self::read_line() else {
+            return Ok(Command::Quit);
+        };
+        let builder = match line.trim() {
+            "O" | "o" => builder.symbol(Symbol::O)?,
+            "X" | "x" => builder.symbol(Symbol::X)?,
+            "U" | "u" => return Ok(Command::Undo),
+            "R" | "r" => return Ok(Command::Redo),
+            line => {
+                let (command, path) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                let path = match path.trim() {
+                    "" => DEFAULT_RECORD_PATH.to_string(),
+                    path => path.to_string(),
+                };
+                return Ok(match command {
+                    "save" => Command::Save(path),
+                    "load" => Command::Load(path),
+                    _ => Command::Play(builder.parse(line)?),
+                });
+            }
         };
 
         self.view.display_prompt_totem();
-        let builder = match stdin().lines().next().unwrap().as_deref() {
-            Ok(line) => {
-                let (x, y) = line.split_once(',').unwrap();
-                builder.totem_pos((x.parse().unwrap(), y.parse().unwrap()))?
-            }
-            _ => panic!("Input/Output error"),
+        let Some(line) = Self::read_line() else {
+            return Ok(Command::Quit);
         };
+        let builder = builder.totem_pos(line.parse::<Position>()?)?;
 
         self.view.display_prompt_piece();
-        let moves = match stdin().lines().next().unwrap().as_deref() {
-            Ok(line) => {
-                let (x, y) = line.split_once(',').unwrap();
-                builder.piece_pos((x.parse().unwrap(), y.parse().unwrap()))?
-            }
-            _ => panic!("Input/Output error"),
+        let Some(line) = Self::read_line() else {
+            return Ok(Command::Quit);
         };
+        let moves = builder.piece_pos(line.parse::<Position>()?)?;
 
-        Ok(moves)
+        Ok(Command::Play(moves))
     }
 
     pub fn start(&mut self) {
         loop {
             self.view.display_game(&self.game);
+            if self.is_computer_turn() {
+                let color = self.game.current_player().color();
+                self.view.display_computer_thinking(color);
+                let moves = self
+                    .engine
+                    .best_move(&self.game)
+                    .expect("the game is not over");
+                self.view.display_computer_move(color, moves);
+                match self.game.play(moves) {
+                    Ok(()) if self.game.state().is_over() => break,
+                    Ok(()) => {}
+                    Err(err) => self.view.display_error(err),
+                }
+                continue;
+            }
             match self.prompt_moves() {
-                Ok(moves) => match self.game.play(moves) {
+                Ok(Command::Quit) => return,
+                Ok(Command::Undo) => {
+                    // Take back the computer's move along with the player's
+                    if self.game.undo().is_some() && self.is_computer_turn() {
+                        self.game.undo();
+                    }
+                }
+                Ok(Command::Redo) => {
+                    if self.game.redo().is_some() && self.is_computer_turn() {
+                        self.game.redo();
+                    }
+                }
+                Ok(Command::Save(path)) => match self.game.save(&path) {
+                    Ok(()) => self.view.display_saved(&path),
+                    Err(err) => self.view.display_error(err),
+                },
+                Ok(Command::Load(path)) => match Game::load(&path) {
+                    Ok(game) => {
+                        self.game = game;
+                        if self.game.state().is_over() {
+                            break;
+                        }
+                    }
+                    Err(err) => self.view.display_error(err),
+                },
+                Ok(Command::Play(moves)) => match self.game.play(moves) {
                     Ok(()) => {
                         if self.game.state().is_over() {
                             break;
@@ -9,3 +9,67 @@ pub use console::Controller;
 
 #[cfg(feature = "tui")]
 pub use tui::{Controller, UIState};
+
+use {
+    oxono::{ai::AlphaBeta, model::Color},
+    std::time::Duration,
+};
+
+/// Command line options shared by the front-ends.
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    /// Color played by the computer, if any.
+    pub computer: Option<Color>,
+    /// Strength of the computer, from 1 to 5.
+    pub level: u32,
+    /// Number of threads the computer searches with.
+    pub threads: usize,
+}
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            computer: None,
+            level: 3,
+            threads: 1,
+        }
+    }
+}
+impl Options {
+    pub const USAGE: &'static str =
+        "Usage: oxono [--computer pink|black] [--level 1-5] [--threads n]";
+
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--computer" => {
+                    options.computer = match args.next().as_deref() {
+                        Some("pink") => Some(Color::Pink),
+                        Some("black") => Some(Color::Black),
+                        _ => return Err("--computer expects pink or black".to_string()),
+                    }
+                }
+                "--level" => {
+                    options.level = match args.next().map(|l| l.parse()) {
+                        Some(Ok(level @ 1..=5)) => level,
+                        _ => return Err("--level expects a number from 1 to 5".to_string()),
+                    }
+                }
+                "--threads" => {
+                    options.threads = match args.next().map(|n| n.parse()) {
+                        Some(Ok(threads @ 1..)) => threads,
+                        _ => return Err("--threads expects a positive number".to_string()),
+                    }
+                }
+                _ => return Err(format!("unknown argument {arg}")),
+            }
+        }
+        Ok(options)
+    }
+    /// The computer opponent matching the chosen level.
+    pub fn engine(&self) -> AlphaBeta {
+        AlphaBeta::new(self.level + 1)
+            .with_time_limit(Duration::from_millis(250) * self.level)
+            .with_threads(self.threads)
+    }
+}
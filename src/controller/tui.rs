@@ -1,13 +1,28 @@
 use {
-    crate::{
-        model::{Game, MoveApplyError, MovesBuilderError, Position, Square, Symbol},
-        view,
-    },
+    super::Options,
+    crate::view,
     crossterm::event::{self},
+    oxono::{
+        ai::{AlphaBeta, Engine},
+        model::{Color, Error, Game, Moves, Position, Square, Symbol},
+    },
     ratatui::DefaultTerminal,
-    std::io,
+    std::{
+        io,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            mpsc::{self, Receiver, TryRecvError},
+            Arc, Mutex, PoisonError,
+        },
+        thread,
+        time::{Duration, Instant},
+    },
 };
 
+const RECORD_PATH: &str = "oxono.game";
+/// How long the move played by the computer blinks before being steadily highlighted.
+const COMPUTER_MOVE_BLINK: Duration = Duration::from_millis(1200);
+
 pub enum UserMessage {
     Quit,
     Select,
@@ -15,20 +30,24 @@ pub enum UserMessage {
     Up,
     Left,
     Down,
+    Undo,
+    Redo,
+    Save,
+    Load,
+    SwitchComputer,
     None,
 }
 
-pub enum MoveError {
-    MoveBuilderError(MovesBuilderError),
-    MoveApplyError(MoveApplyError),
-}
-
 #[derive(Default)]
 pub struct UIState {
     selected_pos: Option<Position>,
     selected_symbol: Option<Symbol>,
     selected_totem_pos: Option<Position>,
-    error: Option<MoveError>,
+    error: Option<Error>,
+    notice: Option<String>,
+    computer: Option<Color>,
+    thinking: bool,
+    computer_move: Option<(Moves, Instant)>,
 }
 impl UIState {
     pub fn reset_selection(&mut self) {
@@ -46,10 +65,24 @@ impl UIState {
         self.selected_totem_pos
     }
     pub fn error_msg(&self) -> Option<String> {
-        self.error.as_ref().map(|e| match e {
-            MoveError::MoveBuilderError(error) => format!("{error:?}"),
-            MoveError::MoveApplyError(error) => format!("{error:?}"),
-        })
+        self.error.as_ref().map(ToString::to_string)
+    }
+    pub fn notice(&self) -> Option<&str> {
+        self.notice.as_deref()
+    }
+    /// Color played by the computer, if any.
+    pub fn computer(&self) -> Option<Color> {
+        self.computer
+    }
+    pub fn is_thinking(&self) -> bool {
+        self.thinking
+    }
+    /// The last move played by the computer, blinking for a while after it was played.
+    pub fn highlighted_move(&self) -> Option<Moves> {
+        let (moves, played_at) = self.computer_move?;
+        let elapsed = played_at.elapsed();
+        let visible = elapsed >= COMPUTER_MOVE_BLINK || (elapsed.as_millis() / 200) % 2 == 0;
+        visible.then_some(moves)
     }
 
     fn has_to_select_totem(&mut self) -> Option<&mut Position> {
@@ -61,21 +94,81 @@ impl UIState {
     }
 }
 
-#[derive(Default)]
 pub struct Controller {
     game: Game,
     ui: UIState,
+    /// Kept from one move to the next along with its transposition table, locked by the
+    /// thread searching.
+    engine: Arc<Mutex<AlphaBeta>>,
+    /// The computer's ongoing search: where its move comes from and how to stop it.
+    thinking: Option<(Receiver<Option<Moves>>, Arc<AtomicBool>)>,
 }
 impl Controller {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(options: Options) -> Self {
+        Self {
+            game: Game::default(),
+            ui: UIState {
+                computer: options.computer,
+                ..Default::default()
+            },
+            engine: Arc::new(Mutex::new(options.engine())),
+            thinking: None,
+        }
+    }
+    fn is_computer_turn(&self) -> bool {
+        !self.game.state().is_over() && self.ui.computer == Some(self.game.current_player().color())
+    }
+    /// Starts the computer's search on a background thread when it has to play, and plays
+    /// its move once found.
+    fn update_computer(&mut self) {
+        if let Some((thinking, _)) = &self.thinking {
+            match thinking.try_recv() {
+                Ok(Some(moves)) => {
+                    if let Err(err) = self.game.play(moves) {
+                        self.ui.error = Some(err.into());
+                    }
+                    self.ui.computer_move = Some((moves, Instant::now()));
+                    self.thinking = None;
+                }
+                Ok(None) | Err(TryRecvError::Disconnected) => self.thinking = None,
+                Err(TryRecvError::Empty) => {}
+            }
+        } else if self.is_computer_turn() {
+            let (sender, receiver) = mpsc::channel();
+            let game = self.game.clone();
+            let stop = Arc::new(AtomicBool::new(false));
+            let (engine, search_stop) = (self.engine.clone(), stop.clone());
+            thread::spawn(move || {
+                // A cancelled search may still hold the engine for a moment
+                let mut engine = engine.lock().unwrap_or_else(PoisonError::into_inner);
+                engine.set_stop(search_stop);
+                let _ = sender.send(engine.best_move(&game));
+            });
+            self.thinking = Some((receiver, stop));
+        }
+        self.ui.thinking = self.thinking.is_some();
+    }
+    /// Stops the computer's ongoing search, if any, after the game has changed.
+    fn cancel_computer(&mut self) {
+        if let Some((_, stop)) = self.thinking.take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+        self.ui.computer_move = None;
     }
     fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
         loop {
+            self.update_computer();
             terminal.draw(|frame| view::draw(&self.game, &self.ui, frame))?;
 
-            match self.handle_events()? {
-                UserMessage::Quit => break,
+            let message = self.handle_events()?;
+            if !matches!(message, UserMessage::None) {
+                let _ = self.ui.notice.take();
+            }
+            match message {
+                UserMessage::Quit => {
+                    self.cancel_computer();
+                    break;
+                }
                 UserMessage::Select => {
                     if self.ui.error.take().is_some() {
                         continue;
@@ -88,7 +181,7 @@ impl Controller {
                     if self.ui.selected_symbol.is_none() {
                         if let Square::Totem(symb) = self.game.board().get(pos) {
                             if let Err(err) = builder_1.symbol(*symb) {
-                                self.ui.error = Some(MoveError::MoveBuilderError(err));
+                                self.ui.error = Some(err.into());
                             } else {
                                 self.ui.selected_symbol = Some(*symb);
                             }
@@ -98,7 +191,7 @@ impl Controller {
                     let builder_2 = builder_1.symbol(self.ui.selected_symbol.unwrap()).unwrap();
                     if self.ui.selected_totem_pos.is_none() {
                         if let Err(err) = builder_2.totem_pos(pos) {
-                            self.ui.error = Some(MoveError::MoveBuilderError(err));
+                            self.ui.error = Some(err.into());
                         } else {
                             self.ui.selected_totem_pos = Some(pos);
                         }
@@ -109,14 +202,15 @@ impl Controller {
                         .unwrap();
                     match builder_3.piece_pos(pos) {
                         Err(err) => {
-                            self.ui.error = Some(MoveError::MoveBuilderError(err));
+                            self.ui.error = Some(err.into());
                             continue;
                         }
                         Ok(moves) => {
                             if let Err(err) = self.game.play(moves) {
-                                self.ui.error = Some(MoveError::MoveApplyError(err));
+                                self.ui.error = Some(err.into());
                             }
                             self.ui.reset_selection();
+                            self.ui.computer_move = None;
                         }
                     }
                 }
@@ -180,6 +274,48 @@ impl Controller {
                         self.ui.selected_pos = self.game.board().find(Square::Totem(Symbol::O));
                     }
                 }
+                UserMessage::Undo => {
+                    let _ = self.ui.error.take();
+                    self.cancel_computer();
+                    if self.game.undo().is_some() {
+                        // Take back the computer's move along with the player's
+                        if self.is_computer_turn() {
+                            self.game.undo();
+                        }
+                        self.ui.reset_selection();
+                    }
+                }
+                UserMessage::Redo => {
+                    let _ = self.ui.error.take();
+                    self.cancel_computer();
+                    if self.game.redo().is_some() {
+                        if self.is_computer_turn() {
+                            self.game.redo();
+                        }
+                        self.ui.reset_selection();
+                    }
+                }
+                UserMessage::Save => match self.game.save(RECORD_PATH) {
+                    Ok(()) => self.ui.notice = Some(format!("Game saved to {RECORD_PATH}")),
+                    Err(err) => self.ui.error = Some(err.into()),
+                },
+                UserMessage::Load => match Game::load(RECORD_PATH) {
+                    Ok(game) => {
+                        self.game = game;
+                        self.cancel_computer();
+                        self.ui.reset_selection();
+                        self.ui.notice = Some(format!("Game loaded from {RECORD_PATH}"));
+                    }
+                    Err(err) => self.ui.error = Some(err.into()),
+                },
+                UserMessage::SwitchComputer => {
+                    self.cancel_computer();
+                    self.ui.computer = match self.ui.computer {
+                        None => Some(Color::Black),
+                        Some(Color::Black) => Some(Color::Pink),
+                        Some(Color::Pink) => None,
+                    };
+                }
                 UserMessage::None => {}
             }
         }
@@ -187,14 +323,24 @@ impl Controller {
         Ok(())
     }
     fn handle_events(&self) -> io::Result<UserMessage> {
+        // Don't block so that the computer's move and animations show up
+        if !event::poll(Duration::from_millis(50))? {
+            return Ok(UserMessage::None);
+        }
+        let can_play = !self.game.state().is_over() && !self.is_computer_turn();
         match event::read()? {
             event::Event::Key(key) if key.kind == event::KeyEventKind::Press => match key.code {
                 event::KeyCode::Char('q') => Ok(UserMessage::Quit),
-                event::KeyCode::Enter if !self.game.state().is_over() => Ok(UserMessage::Select),
-                event::KeyCode::Right if !self.game.state().is_over() => Ok(UserMessage::Right),
-                event::KeyCode::Up if !self.game.state().is_over() => Ok(UserMessage::Up),
-                event::KeyCode::Left if !self.game.state().is_over() => Ok(UserMessage::Left),
-                event::KeyCode::Down if !self.game.state().is_over() => Ok(UserMessage::Down),
+                event::KeyCode::Char('u') => Ok(UserMessage::Undo),
+                event::KeyCode::Char('r') => Ok(UserMessage::Redo),
+                event::KeyCode::Char('s') => Ok(UserMessage::Save),
+                event::KeyCode::Char('l') => Ok(UserMessage::Load),
+                event::KeyCode::Char('c') => Ok(UserMessage::SwitchComputer),
+                event::KeyCode::Enter if can_play => Ok(UserMessage::Select),
+                event::KeyCode::Right if can_play => Ok(UserMessage::Right),
+                event::KeyCode::Up if can_play => Ok(UserMessage::Up),
+                event::KeyCode::Left if can_play => Ok(UserMessage::Left),
+                event::KeyCode::Down if can_play => Ok(UserMessage::Down),
                 _ => Ok(UserMessage::None),
             },
             _ => Ok(UserMessage::None),
@@ -0,0 +1,10 @@
+//! Rules engine for the Oxono board game.
+//!
+//! The [`model`] module holds everything needed to play a game: the [`model::Board`],
+//! the [`model::Game`] driving the turns and the [`model::Moves`] builder validating
+//! each move against the rules. The `oxono` binary is a thin front-end on top of it.
+//!
+//! With the `serde` feature, the model types can be serialized and deserialized.
+
+pub mod ai;
+pub mod model;
@@ -1,9 +1,24 @@
 mod controller;
-mod model;
+mod perft;
 mod view;
 
-use controller::Controller;
+use controller::{Controller, Options};
 
 fn main() {
-    Controller::new().start();
+    let mut args = std::env::args().skip(1).peekable();
+    if args.next_if_eq("perft").is_some() {
+        if let Err(err) = perft::run(args) {
+            eprintln!("{err}\n{}", perft::USAGE);
+            std::process::exit(2);
+        }
+        return;
+    }
+    let options = match Options::parse(args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{err}\n{}", Options::USAGE);
+            std::process::exit(2);
+        }
+    };
+    Controller::new(options).start();
 }
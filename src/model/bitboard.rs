@@ -0,0 +1,114 @@
+//! Masks of the 36 squares of the board packed in a `u64`, bit `x + 6 * y` standing for
+//! the square at (x, y), used by [`super::Board`].
+
+/// Every square of the board.
+pub(crate) const FULL: u64 = (1 << 36) - 1;
+
+/// Directions in which a totem can move, in the order they are looked at.
+pub(crate) const RIGHT: usize = 0;
+pub(crate) const UP: usize = 1;
+pub(crate) const LEFT: usize = 2;
+pub(crate) const DOWN: usize = 3;
+
+const fn step(i: usize, direction: usize) -> Option<usize> {
+    let (x, y) = (i % 6, i / 6);
+    match direction {
+        RIGHT if x < 5 => Some(i + 1),
+        UP if y > 0 => Some(i - 6),
+        LEFT if x > 0 => Some(i - 1),
+        DOWN if y < 5 => Some(i + 6),
+        _ => None,
+    }
+}
+
+/// For each direction and each square, the squares met going from it in that direction.
+pub(crate) const RAYS: [[u64; 36]; 4] = {
+    let mut rays = [[0; 36]; 4];
+    let mut direction = 0;
+    while direction < 4 {
+        let mut i = 0;
+        while i < 36 {
+            let mut next = step(i, direction);
+            while let Some(n) = next {
+                rays[direction][i] |= 1 << n;
+                next = step(n, direction);
+            }
+            i += 1;
+        }
+        direction += 1;
+    }
+    rays
+};
+
+/// For each square, the squares directly next to it.
+pub(crate) const NEIGHBOURS: [u64; 36] = {
+    let mut neighbours = [0; 36];
+    let mut i = 0;
+    while i < 36 {
+        let mut direction = 0;
+        while direction < 4 {
+            if let Some(n) = step(i, direction) {
+                neighbours[i] |= 1 << n;
+            }
+            direction += 1;
+        }
+        i += 1;
+    }
+    neighbours
+};
+
+/// The 36 groups of four aligned squares, in the order of
+/// [`super::Position::four_latteral_groups`]: columns first, then rows.
+pub(crate) const LINES: [u64; 36] = {
+    let mut lines = [0; 36];
+    let mut i = 0;
+    while i < 36 {
+        let (n, f) = (i / 3, i % 3);
+        lines[i] = if n < 6 {
+            // Vertical, in column n starting at row f
+            let first = n + f * 6;
+            (1 << first) | (1 << (first + 6)) | (1 << (first + 12)) | (1 << (first + 18))
+        } else {
+            // Horizontal, in row n - 6 starting at column f
+            let first = f + (n - 6) * 6;
+            0b1111 << first
+        };
+        i += 1;
+    }
+    lines
+};
+
+/// The square of `mask` met first going from a square in `direction`, given that `mask`
+/// only holds squares of the ray in that direction.
+pub(crate) fn first_in_ray(mask: u64, direction: usize) -> Option<usize> {
+    match (mask, direction) {
+        (0, _) => None,
+        (_, RIGHT | DOWN) => Some(mask.trailing_zeros() as usize),
+        _ => Some(63 - mask.leading_zeros() as usize),
+    }
+}
+
+/// Iterates over the squares of `mask`, in increasing order.
+pub(crate) fn squares(mut mask: u64) -> impl Iterator<Item = usize> {
+    core::iter::from_fn(move || {
+        let i = mask.trailing_zeros() as usize;
+        mask &= mask.checked_sub(1)?;
+        Some(i)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Position;
+
+    #[test]
+    fn lines_match_four_latteral_groups() {
+        for (line, four) in LINES.iter().zip(Position::four_latteral_groups()) {
+            let mask = four
+                .iter()
+                .fold(0, |mask, p| mask | 1 << (p.x() + p.y() * 6));
+            assert_eq!(*line, mask);
+        }
+    }
+}
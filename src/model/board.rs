@@ -1,6 +1,9 @@
-use super::{moves::MovesData, Color, Moves, Player, Position, Symbol};
+use super::{
+    bitboard, moves::MovesData, zobrist, Color, Moves, Player, Position, RuleViolation, Symbol,
+};
 
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Square {
     Totem(Symbol),
     Piece(Symbol, Color),
@@ -26,6 +29,8 @@ impl Square {
 }
 
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 struct PiecesCount(i32);
 impl Default for PiecesCount {
     fn default() -> Self {
@@ -44,9 +49,15 @@ impl PiecesCount {
             None
         }
     }
+    pub fn put_back(&mut self) {
+        if let Self(n @ 0..8) = self {
+            *n += 1;
+        }
+    }
 }
 
-#[derive(Debug, Default)]
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Pieces {
     x_pink: PiecesCount,
     x_black: PiecesCount,
@@ -62,6 +73,14 @@ impl Pieces {
             (Symbol::O, Color::Black) => self.o_black.take(symbol, color),
         }
     }
+    fn put_back(&mut self, symbol: Symbol, color: Color) {
+        match (symbol, color) {
+            (Symbol::X, Color::Pink) => self.x_pink.put_back(),
+            (Symbol::X, Color::Black) => self.x_black.put_back(),
+            (Symbol::O, Color::Pink) => self.o_pink.put_back(),
+            (Symbol::O, Color::Black) => self.o_black.put_back(),
+        }
+    }
     pub fn has_left(&self, symbol: Symbol, color: Color) -> bool {
         !match (symbol, color) {
             (Symbol::X, Color::Pink) => matches!(self.x_pink, PiecesCount(0)),
@@ -80,11 +99,136 @@ impl Pieces {
     }
 }
 
-#[derive(Debug)]
+/// Reasons for [`Board::make`] to refuse a move, leaving the board untouched.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MoveApplyError {
-    WrongTotemPosition,
-    NotEmpty,
-    NoPieceLeft,
+    /// The totem of this symbol isn't on the given old position.
+    WrongTotemPosition { symbol: Symbol, pos: Position },
+    /// The piece would be placed on this square, occupied once the totem has moved.
+    NotEmpty(Position),
+    /// The move breaks the rules.
+    Rule(RuleViolation),
+    /// The game is already over, see [`Game::make`](super::Game::make).
+    GameOver,
+}
+impl core::fmt::Display for MoveApplyError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MoveApplyError::WrongTotemPosition { symbol, pos } => {
+                write!(f, "the {symbol} totem isn't on {pos}")
+            }
+            MoveApplyError::NotEmpty(pos) => write!(f, "the square {pos} isn't empty"),
+            MoveApplyError::Rule(violation) => violation.fmt(f),
+            MoveApplyError::GameOver => write!(f, "the game is over"),
+        }
+    }
+}
+impl std::error::Error for MoveApplyError {}
+impl From<RuleViolation> for MoveApplyError {
+    fn from(violation: RuleViolation) -> Self {
+        MoveApplyError::Rule(violation)
+    }
+}
+
+/// Errors met while reading a position in the setup notation, see [`Board::from_fen`].
+#[derive(Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FenError {
+    /// A field is missing or there are too many of them.
+    WrongFieldCount,
+    /// The given row (from 0, top of the board) doesn't describe 6 squares.
+    InvalidRow(usize),
+    /// The pieces counters aren't four numbers between 0 and 8.
+    InvalidPiecesCount,
+    /// The side to move is neither `p` nor `b`.
+    InvalidSide,
+    /// There isn't exactly one totem of this symbol on the board.
+    TotemCount(Symbol),
+    /// The pieces of this kind on the board and in the counter don't add up to 8.
+    PiecesMismatch(Symbol, Color),
+}
+impl core::fmt::Display for FenError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FenError::WrongFieldCount => write!(f, "wrong number of fields"),
+            FenError::InvalidRow(row) => write!(f, "row {row} doesn't describe 6 squares"),
+            FenError::InvalidPiecesCount => {
+                write!(f, "the pieces counters must be four numbers from 0 to 8")
+            }
+            FenError::InvalidSide => write!(f, "the side to move must be p or b"),
+            FenError::TotemCount(symbol) => {
+                write!(f, "there must be exactly one {symbol} totem on the board")
+            }
+            FenError::PiecesMismatch(symbol, color) => write!(
+                f,
+                "the {color} {symbol} pieces on the board and left don't add up to 8"
+            ),
+        }
+    }
+}
+impl std::error::Error for FenError {}
+
+/// Token returned by [`Board::make`], needed to take the move back with [`Board::unmake`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[must_use]
+pub struct BoardUndo {
+    moves: Moves,
+    color: Color,
+}
+impl BoardUndo {
+    /// The move that was made.
+    pub fn moves(&self) -> Moves {
+        self.moves
+    }
+    /// The color of the piece that was placed.
+    pub fn color(&self) -> Color {
+        self.color
+    }
+}
+
+/// What the four pieces of a [`WinningLine`] have in common.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineKind {
+    Color,
+    Symbol,
+    Both,
+}
+
+/// Four aligned pieces of the same color or symbol, see [`Board::winning_line`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WinningLine {
+    positions: [Position; 4],
+    kind: LineKind,
+}
+impl WinningLine {
+    pub fn positions(&self) -> [Position; 4] {
+        self.positions
+    }
+    pub fn kind(&self) -> LineKind {
+        self.kind
+    }
+    pub fn contains(&self, pos: Position) -> bool {
+        self.positions.contains(&pos)
+    }
+}
+
+/// A square where a player can place a piece of a symbol and win, see [`Board::threats`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Threat {
+    symbol: Symbol,
+    pos: Position,
+}
+impl Threat {
+    pub fn symbol(&self) -> Symbol {
+        self.symbol
+    }
+    pub fn pos(&self) -> Position {
+        self.pos
+    }
 }
 
 #[derive(Debug)]
@@ -94,44 +238,97 @@ pub enum TotemStatus {
     Free,
 }
 
-#[derive(Debug)]
+/// The squares are stored as bitboards, see [`bitboard`]: one mask of the pieces of each
+/// color, one of the pieces of each symbol and one holding the totem of each symbol, all
+/// indexed by the discriminant of [`Color`] or [`Symbol`].
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "super::serialize::BoardData",
+        try_from = "super::serialize::BoardData"
+    )
+)]
 pub struct Board {
-    squares: [Square; 36],
+    colors: [u64; 2],
+    symbols: [u64; 2],
+    totems: [u64; 2],
     pieces: Pieces,
+    /// Zobrist hash of the position, see [`Board::hash`].
+    hash: u64,
 }
 impl Default for Board {
     fn default() -> Self {
-        let mut new = Self {
-            squares: [Square::Empty; 36],
-            pieces: Pieces::default(),
-        };
-        new.squares[14] = Square::Totem(Symbol::X);
-        new.squares[21] = Square::Totem(Symbol::O);
+        let mut new = Self::empty();
+        new.set(14, Square::Totem(Symbol::X));
+        new.set(21, Square::Totem(Symbol::O));
+        new.hash = new.compute_hash();
         new
     }
 }
+/// Two boards are equal if they hold the same squares and pieces with the same side to move.
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash
+            && self.colors == other.colors
+            && self.symbols == other.symbols
+            && self.totems == other.totems
+            && [Symbol::X, Symbol::O].into_iter().all(|symbol| {
+                [Color::Pink, Color::Black]
+                    .into_iter()
+                    .all(|color| self.pieces.get(symbol, color) == other.pieces.get(symbol, color))
+            })
+    }
+}
+impl Eq for Board {}
+impl core::hash::Hash for Board {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
 impl core::fmt::Display for Board {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.display_with(|f, _, square| write!(f, "{square}"))
+            .fmt(f)
+    }
+}
+
+/// Writes a [`Board`] as a grid, each square being written by a function given to
+/// [`Board::display_with`].
+pub struct BoardDisplay<'b, F> {
+    board: &'b Board,
+    square: F,
+}
+impl<F> core::fmt::Display for BoardDisplay<'_, F>
+where
+    F: Fn(&mut core::fmt::Formatter<'_>, Position, &Square) -> core::fmt::Result,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let pieces = &self.board.pieces;
         writeln!(
             f,
             "Pink's pieces: X: {}, O: {}\n\n",
-            self.pieces.x_pink.get(),
-            self.pieces.o_pink.get()
+            pieces.x_pink.get(),
+            pieces.o_pink.get()
         )?;
         writeln!(f, "    0  1  2  3  4  5")?;
         writeln!(f, "  +--+--+--+--+--+--+")?;
-        for y in 0..6 {
-            write!(f, "{} |", y)?;
-            for x in 0..6 {
-                write!(f, "{}|", self.get(Position::new(x, y)))?;
+        for pos in Position::all() {
+            if pos.x() == 0 {
+                write!(f, "{} |", pos.y())?;
+            }
+            (self.square)(f, pos, self.board.get(pos))?;
+            write!(f, "|")?;
+            if pos.x() == 5 {
+                writeln!(f, "\n  +--+--+--+--+--+--+")?;
             }
-            writeln!(f, "\n  +--+--+--+--+--+--+")?;
         }
         write!(
             f,
             "\n\nBlack's pieces: X: {}, O: {}",
-            self.pieces.x_black.get(),
-            self.pieces.o_black.get()
+            pieces.x_black.get(),
+            pieces.o_black.get()
         )?;
 
         Ok(())
@@ -147,25 +344,264 @@ impl Board {
         let y = (inner / 6) as u8;
         (x, y).try_into().ok()
     }
+    /// A board without any totem or piece on it.
+    fn empty() -> Self {
+        Self {
+            colors: [0; 2],
+            symbols: [0; 2],
+            totems: [0; 2],
+            pieces: Pieces::default(),
+            hash: 0,
+        }
+    }
+    fn square_at(&self, i: usize) -> Square {
+        let bit = 1 << i;
+        let symbol = if self.symbols[Symbol::X as usize] & bit != 0 {
+            Symbol::X
+        } else {
+            Symbol::O
+        };
+        if self.totems[Symbol::X as usize] & bit != 0 {
+            Square::Totem(Symbol::X)
+        } else if self.totems[Symbol::O as usize] & bit != 0 {
+            Square::Totem(Symbol::O)
+        } else if self.colors[Color::Pink as usize] & bit != 0 {
+            Square::Piece(symbol, Color::Pink)
+        } else if self.colors[Color::Black as usize] & bit != 0 {
+            Square::Piece(symbol, Color::Black)
+        } else {
+            Square::Empty
+        }
+    }
+    fn empty_squares(&self) -> u64 {
+        let occupied = self.colors[0] | self.colors[1] | self.totems[0] | self.totems[1];
+        !occupied & bitboard::FULL
+    }
+    /// Whether the squares of `mask` all hold pieces of the same color or the same symbol.
+    fn is_aligned(&self, mask: u64) -> bool {
+        self.colors
+            .iter()
+            .chain(&self.symbols)
+            .any(|pieces| pieces & mask == mask)
+    }
+    fn compute_hash(&self) -> u64 {
+        let squares = (0..36).fold(0, |hash, i| hash ^ zobrist::square(i, self.square_at(i)));
+        [Symbol::X, Symbol::O]
+            .into_iter()
+            .flat_map(|symbol| [(symbol, Color::Pink), (symbol, Color::Black)])
+            .fold(squares, |hash, (symbol, color)| {
+                hash ^ zobrist::pieces(symbol, color, self.pieces.get(symbol, color))
+            })
+    }
+    fn set(&mut self, i: usize, square: Square) {
+        let bit = 1 << i;
+        self.hash ^= zobrist::square(i, self.square_at(i)) ^ zobrist::square(i, square);
+        for mask in self
+            .colors
+            .iter_mut()
+            .chain(&mut self.symbols)
+            .chain(&mut self.totems)
+        {
+            *mask &= !bit;
+        }
+        match square {
+            Square::Totem(symbol) => self.totems[symbol as usize] |= bit,
+            Square::Piece(symbol, color) => {
+                self.symbols[symbol as usize] |= bit;
+                self.colors[color as usize] |= bit;
+            }
+            Square::Empty => {}
+        }
+    }
     fn swap(&mut self, pos1: Position, pos2: Position) {
         let i1 = Self::pos_to_inner(pos1);
         let i2 = Self::pos_to_inner(pos2);
-        let (i_min, i_max) = if i1 < i2 { (i1, i2) } else { (i2, i1) };
-        if let [x, .., y] = &mut self.squares[i_min..=i_max] {
-            core::mem::swap(x, y);
+        let (s1, s2) = (self.square_at(i1), self.square_at(i2));
+        self.set(i1, s2);
+        self.set(i2, s1);
+    }
+    /// Places a piece on an empty square, the player having some left.
+    fn place(&mut self, pos: Position, symbol: Symbol, color: Color) {
+        let count = self.pieces.get(symbol, color);
+        let piece = self
+            .pieces
+            .take(symbol, color)
+            .expect("the player has a piece left");
+        self.hash ^=
+            zobrist::pieces(symbol, color, count) ^ zobrist::pieces(symbol, color, count - 1);
+        self.set(Self::pos_to_inner(pos), piece);
+    }
+
+    /// Reads a position written in the setup notation, a FEN-like line made of two fields
+    /// separated by a space:
+    /// - the 6 rows of the board from top (y = 0) to bottom, separated by `/`. Each row lists
+    ///   its squares from left to right, written like [`Square`]'s `Display` (`TX`, `TO`,
+    ///   `PX`, `PO`, `BX`, `BO`), a run of empty squares being written as its length;
+    /// - the pieces left to each player, as `X,O` for Pink then Black, separated by `/`.
+    ///
+    /// The initial position reads `6/6/2TX3/3TO2/6/6 8,8/8,8`. See [`super::Game::from_fen`]
+    /// for the side to move.
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let mut fields = fen.split_whitespace();
+        let (Some(rows), Some(counts), None) = (fields.next(), fields.next(), fields.next()) else {
+            return Err(FenError::WrongFieldCount);
+        };
+
+        let mut squares = [Square::Empty; 36];
+        let rows = rows.split('/').collect::<Vec<_>>();
+        if rows.len() != 6 {
+            return Err(FenError::WrongFieldCount);
+        }
+        for (y, row) in rows.into_iter().enumerate() {
+            let mut x = 0;
+            let mut chars = row.chars();
+            while let Some(c) = chars.next() {
+                let square = match (c, c.to_digit(10)) {
+                    (_, Some(n @ 1..=6)) => {
+                        x += n as usize;
+                        continue;
+                    }
+                    ('T', _) => chars.next().and_then(|s| match s {
+                        'X' => Some(Square::Totem(Symbol::X)),
+                        'O' => Some(Square::Totem(Symbol::O)),
+                        _ => None,
+                    }),
+                    ('P' | 'B', _) => {
+                        let color = if c == 'P' { Color::Pink } else { Color::Black };
+                        chars.next().and_then(|s| match s {
+                            'X' => Some(Square::Piece(Symbol::X, color)),
+                            'O' => Some(Square::Piece(Symbol::O, color)),
+                            _ => None,
+                        })
+                    }
+                    _ => None,
+                };
+                match square {
+                    Some(square) if x < 6 => squares[x + y * 6] = square,
+                    _ => return Err(FenError::InvalidRow(y)),
+                }
+                x += 1;
+            }
+            if x != 6 {
+                return Err(FenError::InvalidRow(y));
+            }
         }
+
+        let counts = counts
+            .split(['/', ','])
+            .map(|n| n.parse().map_err(|_| FenError::InvalidPiecesCount))
+            .collect::<Result<Vec<_>, _>>()?;
+        let [x_pink, o_pink, x_black, o_black] = counts[..] else {
+            return Err(FenError::InvalidPiecesCount);
+        };
+        let pieces = Pieces {
+            x_pink: PiecesCount(x_pink),
+            x_black: PiecesCount(x_black),
+            o_pink: PiecesCount(o_pink),
+            o_black: PiecesCount(o_black),
+        };
+        Self::from_squares(squares, pieces)
     }
-    fn place(&mut self, pos: Position, symbol: Symbol, color: Color) -> Result<(), MoveApplyError> {
-        let i = Self::pos_to_inner(pos);
-        if let Square::Empty = self.squares[i] {
-            if let Some(piece) = self.pieces.take(symbol, color) {
-                self.squares[i] = piece;
-                Ok(())
-            } else {
-                Err(MoveApplyError::NoPieceLeft)
+    /// Creates a board from its squares, row by row, and the pieces left to the players,
+    /// checking they make a valid position.
+    pub(crate) fn from_squares(squares: [Square; 36], pieces: Pieces) -> Result<Self, FenError> {
+        let counts = [Symbol::X, Symbol::O]
+            .into_iter()
+            .flat_map(|symbol| [(symbol, Color::Pink), (symbol, Color::Black)]);
+        if counts
+            .clone()
+            .any(|(symbol, color)| !(0..=8).contains(&pieces.get(symbol, color)))
+        {
+            return Err(FenError::InvalidPiecesCount);
+        }
+        let mut board = Self {
+            pieces,
+            ..Self::empty()
+        };
+        for (i, square) in squares.iter().enumerate() {
+            board.set(i, *square);
+        }
+        board.hash = board.compute_hash();
+
+        for symbol in [Symbol::X, Symbol::O] {
+            let totems = squares.iter().filter(|s| **s == Square::Totem(symbol));
+            if totems.count() != 1 {
+                return Err(FenError::TotemCount(symbol));
+            }
+        }
+        for (symbol, color) in counts {
+            let on_board = squares
+                .iter()
+                .filter(|s| **s == Square::Piece(symbol, color));
+            if on_board.count() as i32 + board.pieces.get(symbol, color) != 8 {
+                return Err(FenError::PiecesMismatch(symbol, color));
+            }
+        }
+
+        Ok(board)
+    }
+    /// Writes this position in the setup notation, see [`Board::from_fen`].
+    pub fn fen(&self) -> String {
+        let mut fen = String::new();
+        for y in 0..6 {
+            if y > 0 {
+                fen.push('/');
             }
+            let mut empty = 0;
+            for square in (0..6).map(|x| self.square_at(x + y * 6)) {
+                if square.is_empty() {
+                    empty += 1;
+                    continue;
+                }
+                if empty > 0 {
+                    fen.push_str(&empty.to_string());
+                    empty = 0;
+                }
+                fen.push_str(&square.to_string());
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+        }
+        fen.push_str(&format!(
+            " {},{}/{},{}",
+            self.pieces.x_pink.get(),
+            self.pieces.o_pink.get(),
+            self.pieces.x_black.get(),
+            self.pieces.o_black.get()
+        ));
+        fen
+    }
+
+    /// Zobrist hash of the position: the squares, the pieces counters and the side to move.
+    ///
+    /// The side to move is considered to change at each move applied, Pink being to move on
+    /// a board created with [`Board::default`] or [`Board::from_fen`].
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+    /// Flips the side to move in the hash of the position.
+    pub(crate) fn toggle_side(&mut self) {
+        self.hash ^= zobrist::SIDE;
+    }
+    /// The side to move, as recorded in the hash of the position, see [`Board::hash`].
+    pub fn side_to_move(&self) -> Color {
+        if self.hash == self.compute_hash() {
+            Color::Pink
         } else {
-            Err(MoveApplyError::NotEmpty)
+            Color::Black
+        }
+    }
+
+    /// Displays the board like its `Display` does, each square being written by `square`,
+    /// for front-ends decorating some of them.
+    pub fn display_with<F>(&self, square: F) -> BoardDisplay<'_, F>
+    where
+        F: Fn(&mut core::fmt::Formatter<'_>, Position, &Square) -> core::fmt::Result,
+    {
+        BoardDisplay {
+            board: self,
+            square,
         }
     }
 
@@ -176,191 +612,155 @@ impl Board {
         self.pieces.has_left(symbol, color)
     }
     pub fn get(&self, pos: Position) -> &Square {
-        &self.squares[Self::pos_to_inner(pos)]
+        match self.square_at(Self::pos_to_inner(pos)) {
+            Square::Totem(Symbol::X) => &Square::Totem(Symbol::X),
+            Square::Totem(Symbol::O) => &Square::Totem(Symbol::O),
+            Square::Piece(Symbol::X, Color::Pink) => &Square::Piece(Symbol::X, Color::Pink),
+            Square::Piece(Symbol::X, Color::Black) => &Square::Piece(Symbol::X, Color::Black),
+            Square::Piece(Symbol::O, Color::Pink) => &Square::Piece(Symbol::O, Color::Pink),
+            Square::Piece(Symbol::O, Color::Black) => &Square::Piece(Symbol::O, Color::Black),
+            Square::Empty => &Square::Empty,
+        }
     }
     pub fn find(&self, square: Square) -> Option<Position> {
-        let inner = self
-            .squares
-            .iter()
-            .enumerate()
-            .find_map(|(i, s)| (s == &square).then_some(i))?;
-        Self::inner_to_pos(inner)
+        let mask = match square {
+            Square::Totem(symbol) => self.totems[symbol as usize],
+            Square::Piece(symbol, color) => {
+                self.symbols[symbol as usize] & self.colors[color as usize]
+            }
+            Square::Empty => self.empty_squares(),
+        };
+        bitboard::squares(mask).next().and_then(Self::inner_to_pos)
     }
     pub fn totem_status(&self, pos: Position) -> TotemStatus {
-        let right = pos.right();
-        let up = pos.up();
-        let left = pos.left();
-        let down = pos.down();
-        let is_enclave = [right, up, left, down]
-            .iter()
-            .filter_map(|p| p.filter(|pp| self.get(*pp).is_empty()))
-            .count()
-            == 0;
-
-        if !is_enclave {
+        let i = Self::pos_to_inner(pos);
+        let empty = self.empty_squares();
+        if bitboard::NEIGHBOURS[i] & empty != 0 {
             TotemStatus::Free
+        } else if bitboard::RAYS.iter().all(|ray| ray[i] & empty == 0) {
+            TotemStatus::FullyEnclave
         } else {
-            let next_empty_right = pos.iter_right().skip(1).find(|p| self.get(*p).is_empty());
-            let next_empty_up = pos.iter_up().skip(1).find(|p| self.get(*p).is_empty());
-            let next_empty_left = pos.iter_left().skip(1).find(|p| self.get(*p).is_empty());
-            let next_empty_down = pos.iter_down().skip(1).find(|p| self.get(*p).is_empty());
-            let is_fully_enclave = [
-                next_empty_right,
-                next_empty_up,
-                next_empty_left,
-                next_empty_down,
-            ]
-            .iter()
-            .all(Option::is_none);
-            if is_fully_enclave {
-                TotemStatus::FullyEnclave
-            } else {
-                TotemStatus::Enclave
-            }
+            TotemStatus::Enclave
         }
     }
     pub fn totem_valid_moves(&self, pos: Position) -> impl Iterator<Item = Position> + '_ {
-        struct TotemValidMoves<'b>(&'b Board, Position);
-        enum TotemValidMovesIter<'b> {
-            EmptySquares(&'b Board, usize),
-            LatteralFirstEmptySquares(&'b Board, Position, usize),
-            EmptyNeighbours(&'b Board, Position, Position, usize),
-        }
-        impl<'b> Iterator for TotemValidMovesIter<'b> {
-            type Item = Position;
-            fn next(&mut self) -> Option<Self::Item> {
-                match self {
-                    TotemValidMovesIter::EmptySquares(board, i) => loop {
-                        let pos = Board::inner_to_pos(*i)?;
-                        *i += 1;
-                        if board.get(pos).is_empty() {
-                            break Some(pos);
-                        }
-                    },
-                    TotemValidMovesIter::LatteralFirstEmptySquares(board, pos, i) => loop {
-                        let next = match *i {
-                            0 => pos.iter_right().find(|s| board.get(*s).is_empty()),
-                            1 => pos.iter_up().find(|s| board.get(*s).is_empty()),
-                            2 => pos.iter_left().find(|s| board.get(*s).is_empty()),
-                            3 => pos.iter_down().find(|s| board.get(*s).is_empty()),
-                            _ => break None,
-                        };
-                        *i += 1;
-                        if next.is_some() {
-                            break next;
-                        }
-                    },
-                    TotemValidMovesIter::EmptyNeighbours(board, pos, curr, i) => loop {
-                        let next = {
-                            let next = match *i {
-                                0 => curr.right(),
-                                1 => curr.up(),
-                                2 => curr.left(),
-                                3 => curr.down(),
-                                _ => break None,
-                            };
-                            if next.map(|p| board.get(p).is_empty()).unwrap_or_default() {
-                                *curr = next.unwrap();
-                            } else {
-                                *i += 1;
-                                *curr = *pos;
-                                continue;
-                            }
-                            Some(*curr)
-                        };
-                        if next.is_some() {
-                            break next;
-                        }
-                    },
-                }
-            }
-        }
-        impl<'b> IntoIterator for TotemValidMoves<'b> {
-            type IntoIter = TotemValidMovesIter<'b>;
-            type Item = Position;
-            fn into_iter(self) -> Self::IntoIter {
-                let Self(board, pos) = self;
-                match board.totem_status(pos) {
-                    TotemStatus::FullyEnclave => TotemValidMovesIter::EmptySquares(board, 0),
-                    TotemStatus::Enclave => {
-                        TotemValidMovesIter::LatteralFirstEmptySquares(board, pos, 0)
-                    }
-                    TotemStatus::Free => TotemValidMovesIter::EmptyNeighbours(board, pos, pos, 0),
-                }
-            }
-        }
-        TotemValidMoves(self, pos).into_iter()
+        let i = Self::pos_to_inner(pos);
+        let empty = self.empty_squares();
+        let directions = [
+            bitboard::RIGHT,
+            bitboard::UP,
+            bitboard::LEFT,
+            bitboard::DOWN,
+        ];
+        let destinations = match self.totem_status(pos) {
+            // Anywhere
+            TotemStatus::FullyEnclave => empty,
+            // On the first empty square of each line
+            TotemStatus::Enclave => directions
+                .into_iter()
+                .filter_map(|d| bitboard::first_in_ray(bitboard::RAYS[d][i] & empty, d))
+                .fold(0, |mask, n| mask | 1 << n),
+            // As far as possible on each line, without jumping
+            TotemStatus::Free => directions.into_iter().fold(0, |mask, d| {
+                let ray = bitboard::RAYS[d][i];
+                let blocked = bitboard::first_in_ray(ray & !empty, d)
+                    .map_or(0, |b| bitboard::RAYS[d][b] | 1 << b);
+                mask | ray & !blocked
+            }),
+        };
+        bitboard::squares(destinations).filter_map(Self::inner_to_pos)
     }
     pub fn piece_valid_moves(
         &self,
         totem_pos: Position,
         symbol: Symbol,
     ) -> impl Iterator<Item = Position> + '_ {
-        enum Eiter<A, B> {
-            A(A),
-            B(B),
-        }
-        impl<A: Iterator<Item = Position>, B: Iterator<Item = Position>> Iterator for Eiter<A, B> {
-            type Item = Position;
-            fn next(&mut self) -> Option<Self::Item> {
-                match self {
-                    Eiter::A(a) => a.next(),
-                    Eiter::B(b) => b.next(),
-                }
-            }
-        }
-        let right = totem_pos.right();
-        let up = totem_pos.up();
-        let left = totem_pos.left();
-        let down = totem_pos.down();
-        let actual_totem_pos = self.find(Square::Totem(symbol)).unwrap();
-        let usual_case = [right, up, left, down].into_iter().filter_map(move |p| {
-            p.filter(|pp| {
-                let sq = self.get(*pp);
-                sq.is_empty() || pp == &actual_totem_pos
-            })
-        });
-        let empty = self
-            .squares
-            .iter()
-            .copied()
-            .enumerate()
-            .filter_map(|(i, s)| s.is_empty().then_some(i).and_then(Self::inner_to_pos));
-        if usual_case.clone().count() == 0 {
-            Eiter::A(
-                empty
-                    .chain(core::iter::once(actual_totem_pos))
-                    .filter(move |p| *p != totem_pos),
-            )
+        let i = Self::pos_to_inner(totem_pos);
+        // The totem leaves its actual position to go to `totem_pos`
+        let free = self.empty_squares() | self.totems[symbol as usize];
+        let usual_case = bitboard::NEIGHBOURS[i] & free;
+        let targets = if usual_case == 0 {
+            free & !(1 << i)
         } else {
-            Eiter::B(usual_case)
-        }
+            usual_case
+        };
+        bitboard::squares(targets).filter_map(Self::inner_to_pos)
+    }
+    /// Lists every complete move `color` can play on this board, for both symbols it still
+    /// has pieces of.
+    pub fn legal_moves(&self, color: Color) -> impl Iterator<Item = Moves> + '_ {
+        [Symbol::X, Symbol::O]
+            .into_iter()
+            .filter(move |symbol| self.has_left_piece(*symbol, color))
+            .filter_map(|symbol| Some((symbol, self.find(Square::Totem(symbol))?)))
+            .flat_map(move |(symbol, totem_old_pos)| {
+                self.totem_valid_moves(totem_old_pos)
+                    .flat_map(move |totem_new_pos| {
+                        self.piece_valid_moves(totem_new_pos, symbol)
+                            .map(move |piece_pos| {
+                                Moves::new(symbol, totem_old_pos, totem_new_pos, piece_pos)
+                            })
+                    })
+            })
     }
     pub fn wins(&self, four: [Position; 4]) -> bool {
-        let squares = four.iter().map(|p| self.get(*p));
-        if !squares.clone().all(|s| matches!(s, &Square::Piece(_, _))) {
-            return false;
-        }
-        let Square::Piece(symbol, color) = squares.clone().next().unwrap() else {
-            unreachable!()
-        };
-        let mut same_symbol = true;
-        let mut same_color = true;
-        for sq in squares {
-            let Square::Piece(s, c) = sq else {
-                unreachable!()
-            };
-            if s != symbol {
-                same_symbol = false;
-            }
-            if c != color {
-                same_color = false;
-            }
-            if !same_color && !same_symbol {
-                return false;
+        let mask = four
+            .iter()
+            .fold(0, |mask, p| mask | 1 << Self::pos_to_inner(*p));
+        self.is_aligned(mask)
+    }
+    /// Lists the squares where `color` can place a piece and win this turn: those completing
+    /// four of its color or of the piece's symbol, next to which the totem of that symbol can
+    /// move. X threats come first, then O ones, each in the order of [`Position::all`].
+    pub fn threats(&self, color: Color) -> Vec<Threat> {
+        let mut threats = Vec::new();
+        for symbol in [Symbol::X, Symbol::O] {
+            if !self.has_left_piece(symbol, color) {
+                continue;
             }
+            let Some(totem) = self.find(Square::Totem(symbol)) else {
+                continue;
+            };
+            let reachable = self
+                .totem_valid_moves(totem)
+                .flat_map(|to| self.piece_valid_moves(to, symbol))
+                .fold(0, |mask, pos| mask | 1 << Self::pos_to_inner(pos));
+            let colors = self.colors[color as usize];
+            let symbols = self.symbols[symbol as usize];
+            let winning = bitboard::squares(reachable).filter(|i| {
+                bitboard::LINES.iter().any(|&line| {
+                    let completed = |pieces: u64| (pieces | 1 << i) & line == line;
+                    completed(colors) || completed(symbols)
+                })
+            });
+            threats.extend(
+                winning
+                    .filter_map(Self::inner_to_pos)
+                    .map(|pos| Threat { symbol, pos }),
+            );
         }
-        true
+        threats
+    }
+    /// Whether any of the [`Position::four_latteral_groups`] [`Board::wins`].
+    pub(crate) fn has_alignment(&self) -> bool {
+        bitboard::LINES.iter().any(|line| self.is_aligned(*line))
+    }
+    /// The first of the [`Position::four_latteral_groups`] the board [`Board::wins`] on.
+    pub fn winning_line(&self) -> Option<WinningLine> {
+        bitboard::LINES
+            .iter()
+            .zip(Position::four_latteral_groups())
+            .find_map(|(&mask, positions)| {
+                let color = self.colors.iter().any(|pieces| pieces & mask == mask);
+                let symbol = self.symbols.iter().any(|pieces| pieces & mask == mask);
+                let kind = match (color, symbol) {
+                    (true, true) => LineKind::Both,
+                    (true, false) => LineKind::Color,
+                    (false, true) => LineKind::Symbol,
+                    (false, false) => return None,
+                };
+                Some(WinningLine { positions, kind })
+            })
     }
     pub fn no_more_pieces(&self) -> bool {
         !self.pieces.has_left(Symbol::O, Color::Pink)
@@ -370,6 +770,10 @@ impl Board {
     }
 
     pub fn apply(&mut self, moves: Moves, player: &Player) -> Result<(), MoveApplyError> {
+        self.make(moves, player.color()).map(|_| ())
+    }
+    /// Checks that `color` can play `moves` on this board.
+    pub fn validate(&self, moves: Moves, color: Color) -> Result<(), MoveApplyError> {
         let MovesData {
             symbol,
             totem_old_pos,
@@ -377,15 +781,265 @@ impl Board {
             piece_pos,
         } = moves.into_data();
 
-        // Try to move the totem
         if self.get(totem_old_pos) != &Square::Totem(symbol) {
-            return Err(MoveApplyError::WrongTotemPosition);
+            return Err(MoveApplyError::WrongTotemPosition {
+                symbol,
+                pos: totem_old_pos,
+            });
+        }
+        if !self
+            .totem_valid_moves(totem_old_pos)
+            .any(|pos| pos == totem_new_pos)
+        {
+            return Err(RuleViolation::InvalidTotemMove {
+                symbol,
+                from: totem_old_pos,
+                to: totem_new_pos,
+                allowed: self.totem_valid_moves(totem_old_pos).collect(),
+            }
+            .into());
+        }
+        // The totem leaves its old position for an empty square
+        let empty = piece_pos == totem_old_pos || self.get(piece_pos) == &Square::Empty;
+        if !empty || piece_pos == totem_new_pos {
+            return Err(MoveApplyError::NotEmpty(piece_pos));
+        }
+        if !self.has_left_piece(symbol, color) {
+            return Err(RuleViolation::NoPieceLeft {
+                symbol,
+                color,
+                other_left: self.pieces.get(symbol.opposite(), color),
+            }
+            .into());
+        }
+        if !self
+            .piece_valid_moves(totem_new_pos, symbol)
+            .any(|pos| pos == piece_pos)
+        {
+            return Err(RuleViolation::InvalidPiecePlacement {
+                symbol,
+                pos: piece_pos,
+                totem: totem_new_pos,
+                allowed: self.piece_valid_moves(totem_new_pos, symbol).collect(),
+            }
+            .into());
         }
+        Ok(())
+    }
+    /// Applies a move placing a piece of the given color, without allocating. The returned
+    /// token takes the move back when given to [`Board::unmake`].
+    ///
+    /// The whole move is checked with [`Board::validate`] first: the board is left untouched
+    /// when an error is returned.
+    pub fn make(&mut self, moves: Moves, color: Color) -> Result<BoardUndo, MoveApplyError> {
+        self.validate(moves, color)?;
+        let MovesData {
+            symbol,
+            totem_old_pos,
+            totem_new_pos,
+            piece_pos,
+        } = moves.into_data();
+
         self.swap(totem_old_pos, totem_new_pos);
+        self.place(piece_pos, symbol, color);
+        self.toggle_side();
+        Ok(BoardUndo { moves, color })
+    }
+    /// Takes back a move made with [`Board::make`]: the piece returns to its owner and the
+    /// totem to its old position. Moves made afterwards must have been taken back first.
+    pub fn unmake(&mut self, undo: BoardUndo) {
+        let BoardUndo { moves, color } = undo;
+        let MovesData {
+            symbol,
+            totem_old_pos,
+            totem_new_pos,
+            piece_pos,
+        } = moves.into_data();
 
-        // Try to place the piece
-        self.place(piece_pos, symbol, player.color())?;
+        self.set(Self::pos_to_inner(piece_pos), Square::Empty);
+        let count = self.pieces.get(symbol, color);
+        self.pieces.put_back(symbol, color);
+        self.hash ^=
+            zobrist::pieces(symbol, color, count) ^ zobrist::pieces(symbol, color, count + 1);
+        self.swap(totem_new_pos, totem_old_pos);
+        self.toggle_side();
+    }
+}
 
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Game;
+
+    #[test]
+    fn display_with_decorates_squares() {
+        let board = Board::default();
+        let plain = board.display_with(|f, _, square| write!(f, "{square}"));
+        assert_eq!(plain.to_string(), board.to_string());
+        let totem = Position::new(2, 2).unwrap();
+        let marked = board.display_with(|f, pos, square| {
+            if pos == totem {
+                write!(f, "[{square}]")
+            } else {
+                write!(f, "{square}")
+            }
+        });
+        assert!(marked.to_string().contains("|  |[TX]|  |"));
+    }
+
+    #[test]
+    fn hash_is_updated_incrementally() {
+        let mut game = Game::default();
+        let initial = game.board().hash();
+        let mut seen = vec![initial];
+        for notation in ["O2,3-1,3", "X2,1-2,2", "O3,3-3,4", "O2,3-3,3"] {
+            game.play(game.moves_builder().parse(notation).unwrap())
+                .unwrap();
+            let board = game.board();
+            let side = match game.current_player().color() {
+                Color::Pink => 0,
+                Color::Black => zobrist::SIDE,
+            };
+            assert_eq!(board.hash(), board.compute_hash() ^ side);
+            assert_eq!(board.side_to_move(), game.current_player().color());
+            assert_eq!(
+                board.hash(),
+                Game::from_fen(&game.fen()).unwrap().board().hash()
+            );
+            assert!(!seen.contains(&board.hash()));
+            seen.push(board.hash());
+        }
+        while game.undo().is_some() {}
+        assert_eq!(game.board().hash(), initial);
+        assert_eq!(game.board(), &Board::default());
+    }
+
+    #[test]
+    fn threats_are_the_winning_moves() {
+        let p = |x, y| Position::new(x, y).unwrap();
+        let board = Board::from_fen("POPOPO3/6/2TX3/3TO2/6/BXBXBX3 8,5/5,8").unwrap();
+        let threat = |pos| Threat {
+            symbol: Symbol::O,
+            pos,
+        };
+        assert_eq!(board.threats(Color::Pink), [threat(p(3, 0))]);
+        assert_eq!(
+            board.threats(Color::Black),
+            [threat(p(3, 0)), threat(p(3, 5))]
+        );
+
+        for fen in [
+            "BXBX1BX2/3TXPX1/1PXPXPX2/BO3BXPO/POBOTOBO2/POPO2POBO 4,3/4,4",
+            "1PX1POPOTO/POBX2POPO/BO1BO2PO/BXBX1BO1BO/TXPX3PX/BXPXBX3 4,2/3,4",
+            "2PXPX1BX/3BXBOPO/BOPXPOTOPO1/3BOBOPO/3BXTX1/PO1PXBO2 4,3/5,3",
+        ] {
+            let mut board = Board::from_fen(fen).unwrap();
+            for color in [Color::Pink, Color::Black] {
+                let mut winning = Vec::new();
+                for moves in board.legal_moves(color).collect::<Vec<_>>() {
+                    let undo = board.make(moves, color).unwrap();
+                    if board.has_alignment() {
+                        winning.push((moves.symbol(), moves.piece_pos()));
+                    }
+                    board.unmake(undo);
+                }
+                winning.sort_by_key(|(symbol, pos)| (*symbol as usize, pos.y(), pos.x()));
+                winning.dedup();
+                assert!(!winning.is_empty());
+                let threats = board.threats(color);
+                let threats = threats.iter().map(|t| (t.symbol(), t.pos()));
+                assert_eq!(threats.collect::<Vec<_>>(), winning);
+            }
+        }
+    }
+
+    #[test]
+    fn winning_line_works() {
+        let line = |fen| Board::from_fen(fen).unwrap().winning_line();
+        assert_eq!(line("6/6/2TX3/3TO2/6/6 8,8/8,8"), None);
+        let row = [(0, 0), (1, 0), (2, 0), (3, 0)].map(|(x, y)| Position::new(x, y).unwrap());
+        let expected = |kind| {
+            Some(WinningLine {
+                positions: row,
+                kind,
+            })
+        };
+        assert_eq!(
+            line("POPXPOPO2/6/2TX3/3TO2/6/BXBXBX3 7,5/5,8"),
+            expected(LineKind::Color)
+        );
+        assert_eq!(
+            line("POBOPOPO2/6/2TX3/3TO2/6/BXBX4 8,5/6,7"),
+            expected(LineKind::Symbol)
+        );
+        assert_eq!(
+            line("POPOPOPO2/6/2TX3/3TO2/6/BXBXBX3 8,4/5,8"),
+            expected(LineKind::Both)
+        );
+    }
+
+    #[test]
+    fn refused_moves_leave_the_board_untouched() {
+        let pos = |x, y| Position::new(x, y).unwrap();
+        let moves = |symbol, (x1, y1), (x2, y2), (x3, y3)| {
+            Moves::new(symbol, pos(x1, y1), pos(x2, y2), pos(x3, y3))
+        };
+        let initial = Board::default();
+        let cases = [
+            (
+                initial.clone(),
+                moves(Symbol::X, (0, 0), (0, 1), (0, 2)),
+                MoveApplyError::WrongTotemPosition {
+                    symbol: Symbol::X,
+                    pos: pos(0, 0),
+                },
+            ),
+            (
+                initial.clone(),
+                moves(Symbol::X, (2, 2), (1, 1), (1, 2)),
+                MoveApplyError::Rule(RuleViolation::InvalidTotemMove {
+                    symbol: Symbol::X,
+                    from: pos(2, 2),
+                    to: pos(1, 1),
+                    allowed: initial.totem_valid_moves(pos(2, 2)).collect(),
+                }),
+            ),
+            (
+                initial.clone(),
+                moves(Symbol::X, (2, 2), (2, 1), (3, 3)),
+                MoveApplyError::NotEmpty(pos(3, 3)),
+            ),
+            (
+                initial.clone(),
+                moves(Symbol::X, (2, 2), (2, 1), (2, 1)),
+                MoveApplyError::NotEmpty(pos(2, 1)),
+            ),
+            (
+                Board::from_fen("PXPXPXPXPXPX/PXPX4/2TX3/3TO2/6/6 0,8/8,8").unwrap(),
+                moves(Symbol::X, (2, 2), (2, 3), (1, 3)),
+                MoveApplyError::Rule(RuleViolation::NoPieceLeft {
+                    symbol: Symbol::X,
+                    color: Color::Pink,
+                    other_left: 8,
+                }),
+            ),
+            (
+                initial.clone(),
+                moves(Symbol::X, (2, 2), (2, 1), (5, 5)),
+                MoveApplyError::Rule(RuleViolation::InvalidPiecePlacement {
+                    symbol: Symbol::X,
+                    pos: pos(5, 5),
+                    totem: pos(2, 1),
+                    allowed: vec![pos(2, 0), pos(1, 1), pos(3, 1), pos(2, 2)],
+                }),
+            ),
+        ];
+        for (board, moves, error) in cases {
+            let mut after = board.clone();
+            assert_eq!(after.make(moves, Color::Pink), Err(error), "{moves}");
+            assert_eq!(after, board);
+            assert_eq!(after.fen(), board.fen());
+            assert_eq!(after.hash(), board.hash());
+        }
     }
 }
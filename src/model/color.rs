@@ -1,5 +1,24 @@
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Color {
     Pink,
     Black,
 }
+
+impl Color {
+    pub fn opposite(self) -> Self {
+        match self {
+            Color::Pink => Color::Black,
+            Color::Black => Color::Pink,
+        }
+    }
+}
+
+impl core::fmt::Display for Color {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Color::Pink => write!(f, "Pink"),
+            Color::Black => write!(f, "Black"),
+        }
+    }
+}
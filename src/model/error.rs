@@ -0,0 +1,152 @@
+use super::{Color, FenError, MoveApplyError, MovesBuilderError, Position, RecordError, Symbol};
+
+/// A move breaking the rules of Oxono, refused both by the [`super::MovesBuilder`] and by
+/// [`super::Board::validate`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RuleViolation {
+    /// The player has no piece of this symbol left, only `other_left` of the other one.
+    NoPieceLeft {
+        symbol: Symbol,
+        color: Color,
+        other_left: i32,
+    },
+    /// The totem can't go to this position, see [`super::Board::totem_valid_moves`].
+    InvalidTotemMove {
+        symbol: Symbol,
+        from: Position,
+        to: Position,
+        allowed: Vec<Position>,
+    },
+    /// The piece can't be placed there, see [`super::Board::piece_valid_moves`].
+    InvalidPiecePlacement {
+        symbol: Symbol,
+        pos: Position,
+        totem: Position,
+        allowed: Vec<Position>,
+    },
+}
+impl core::fmt::Display for RuleViolation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RuleViolation::NoPieceLeft {
+                symbol,
+                color,
+                other_left,
+            } => write!(
+                f,
+                "{color} has no {symbol} piece left, only {other_left} {} pieces",
+                symbol.opposite()
+            ),
+            RuleViolation::InvalidTotemMove {
+                symbol,
+                from,
+                to,
+                allowed,
+            } => write!(
+                f,
+                "the {symbol} totem on {from} can't move to {to}, it can go to {}",
+                Positions(allowed)
+            ),
+            RuleViolation::InvalidPiecePlacement {
+                symbol,
+                pos,
+                totem,
+                allowed,
+            } => write!(
+                f,
+                "an {symbol} piece can't go on {pos} with the totem on {totem}, \
+                it can go on {}",
+                Positions(allowed)
+            ),
+        }
+    }
+}
+impl std::error::Error for RuleViolation {}
+
+/// Any error of the rules engine, for front-ends that show them to players.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error {
+    /// A move breaks the rules, whether it was being built or applied.
+    Rule(RuleViolation),
+    /// A move can't be built from the player's input.
+    Build(MovesBuilderError),
+    /// A move can't be applied on the board.
+    Apply(MoveApplyError),
+    /// A position in setup notation can't be read.
+    Fen(FenError),
+    /// A game record can't be saved or loaded.
+    Record(RecordError),
+}
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::Rule(err) => err.fmt(f),
+            Error::Build(err) => err.fmt(f),
+            Error::Apply(err) => err.fmt(f),
+            Error::Fen(err) => err.fmt(f),
+            Error::Record(err) => err.fmt(f),
+        }
+    }
+}
+/// `Error` is transparent: it displays the inner error, so its source is the inner one's.
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Rule(err) => err.source(),
+            Error::Build(err) => err.source(),
+            Error::Apply(err) => err.source(),
+            Error::Fen(err) => err.source(),
+            Error::Record(err) => err.source(),
+        }
+    }
+}
+impl From<RuleViolation> for Error {
+    fn from(err: RuleViolation) -> Self {
+        Error::Rule(err)
+    }
+}
+impl From<MovesBuilderError> for Error {
+    fn from(err: MovesBuilderError) -> Self {
+        match err {
+            MovesBuilderError::Rule(err) => Error::Rule(err),
+            err => Error::Build(err),
+        }
+    }
+}
+impl From<MoveApplyError> for Error {
+    fn from(err: MoveApplyError) -> Self {
+        match err {
+            MoveApplyError::Rule(err) => Error::Rule(err),
+            err => Error::Apply(err),
+        }
+    }
+}
+impl From<FenError> for Error {
+    fn from(err: FenError) -> Self {
+        Error::Fen(err)
+    }
+}
+impl From<RecordError> for Error {
+    fn from(err: RecordError) -> Self {
+        Error::Record(err)
+    }
+}
+
+/// Writes a list of positions for error messages, like `2,1 2,4 or 0,3`.
+pub(crate) struct Positions<'p>(pub &'p [Position]);
+impl core::fmt::Display for Positions<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.0 {
+            [] => write!(f, "nowhere"),
+            [pos] => write!(f, "{pos}"),
+            [init @ .., last] => {
+                for pos in init {
+                    write!(f, "{pos} ")?;
+                }
+                write!(f, "or {last}")
+            }
+        }
+    }
+}
@@ -1,20 +1,36 @@
+mod bitboard;
 mod board;
 mod color;
+mod error;
 mod moves;
+mod perft;
 mod player;
 mod position;
+mod record;
+#[cfg(feature = "serde")]
+mod serialize;
 mod symbol;
+mod zobrist;
 
 pub use {
-    board::{Board, MoveApplyError, Square},
+    board::{
+        Board, BoardDisplay, BoardUndo, FenError, LineKind, MoveApplyError, Pieces, Square, Threat,
+        TotemStatus, WinningLine,
+    },
     color::Color,
-    moves::{Moves, MovesBuilderError, MovesBuilderInit},
+    error::{Error, RuleViolation},
+    moves::{
+        Moves, MovesBuilder, MovesBuilderError, MovesBuilderInit, MovesBuilderStateInit,
+        MovesBuilderStateSymbol, MovesBuilderStateTotemPos, MovesNotation,
+    },
     player::{Player, Players},
-    position::Position,
+    position::{OutOfBoard, ParsePositionError, Position},
+    record::RecordError,
     symbol::Symbol,
 };
 
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameState {
     #[default]
     Started,
@@ -28,31 +44,110 @@ impl GameState {
     }
 }
 
-#[derive(Debug, Default)]
+/// Token returned by [`Game::make`], needed to take the move back with [`Game::unmake`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[must_use]
+pub struct GameUndo {
+    board: BoardUndo,
+    state: GameState,
+    turned: bool,
+}
+
+/// A move applied during a [`Game`], along with the color of the player who played it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Played {
+    undo: GameUndo,
+}
+impl Played {
+    pub fn moves(&self) -> Moves {
+        self.undo.board.moves()
+    }
+    pub fn color(&self) -> Color {
+        self.undo.board.color()
+    }
+    /// The piece that was placed on the board by this move.
+    pub fn piece(&self) -> Square {
+        Square::Piece(self.moves().symbol(), self.color())
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "serialize::GameData", try_from = "serialize::GameData")
+)]
 pub struct Game {
     state: GameState,
     board: Board,
     players: Players,
+    history: Vec<Played>,
+    undone: Vec<Played>,
+    /// The position the game started from, in setup notation, if not the initial one.
+    setup: Option<String>,
 }
 impl Game {
-    fn update_state(&mut self) {
-        let state_if_wins = match self.current_player().color() {
-            Color::Pink => GameState::PinkWins,
-            Color::Black => GameState::BlackWins,
+    /// Updates the state of the game once `color` has placed a piece:
+    /// - `color` wins when four pieces of its color or of the same symbol are aligned, even
+    ///   if it was the very last piece;
+    /// - otherwise the game is drawn if the other player can't play, which is the case when
+    ///   all the pieces have been placed.
+    fn update_state(&mut self, color: Color) {
+        self.state = if self.board.has_alignment() {
+            match color {
+                Color::Pink => GameState::PinkWins,
+                Color::Black => GameState::BlackWins,
+            }
+        } else if self.board.legal_moves(color.opposite()).next().is_none() {
+            GameState::Draw
+        } else {
+            GameState::Started
         };
-        for four in Position::four_latteral_groups() {
-            if self.board.wins(four) {
-                self.state = state_if_wins;
-                break;
+    }
+    /// Reads a game position written in the setup notation: the position of the board as
+    /// described in [`Board::from_fen`], followed by the side to move, `p` for Pink or `b`
+    /// for Black.
+    ///
+    /// The initial position reads `6/6/2TX3/3TO2/6/6 8,8/8,8 p`.
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let (board, side) = fen
+            .trim()
+            .rsplit_once(char::is_whitespace)
+            .ok_or(FenError::WrongFieldCount)?;
+        let mut game = Self {
+            board: Board::from_fen(board)?,
+            ..Default::default()
+        };
+        match side {
+            "p" => {}
+            "b" => {
+                game.players.turn();
+                game.board.toggle_side();
             }
+            _ => return Err(FenError::InvalidSide),
         }
-        if self.board.no_more_pieces() {
-            self.state = GameState::Draw;
-        }
+        game.update_state(game.current_player().color().opposite());
+        game.setup = Some(game.fen());
+        Ok(game)
+    }
+    /// Writes the current position in the setup notation, see [`Game::from_fen`].
+    pub fn fen(&self) -> String {
+        let side = match self.current_player().color() {
+            Color::Pink => 'p',
+            Color::Black => 'b',
+        };
+        format!("{} {side}", self.board.fen())
     }
     pub fn state(&self) -> &GameState {
         &self.state
     }
+    /// The four aligned pieces that won the game, if it's won.
+    pub fn winning_line(&self) -> Option<WinningLine> {
+        match self.state {
+            GameState::PinkWins | GameState::BlackWins => self.board.winning_line(),
+            GameState::Started | GameState::Draw => None,
+        }
+    }
     pub fn current_player(&self) -> &Player {
         self.players.current()
     }
@@ -62,18 +157,80 @@ impl Game {
     pub fn moves_builder(&self) -> MovesBuilderInit<'_, '_> {
         Moves::builder(&self.board, self.current_player())
     }
-    pub fn play(&mut self, moves: Moves) -> Result<(), MoveApplyError> {
+    /// Lists every move the current player can play, none if the game is over.
+    pub fn legal_moves(&self) -> impl Iterator<Item = Moves> + '_ {
+        (!self.state.is_over())
+            .then(|| self.board.legal_moves(self.current_player().color()))
+            .into_iter()
+            .flatten()
+    }
+    /// Plays a move without recording it in the history nor allocating, for searches. The
+    /// returned token takes the move back when given to [`Game::unmake`].
+    ///
+    /// Fails with [`MoveApplyError::GameOver`] once the game is over.
+    pub fn make(&mut self, moves: Moves) -> Result<GameUndo, MoveApplyError> {
         if self.state.is_over() {
-            panic!("Game is over")
+            return Err(MoveApplyError::GameOver);
+        }
+        let state = self.state;
+        let color = self.current_player().color();
+        let board = self.board.make(moves, color)?;
+        self.update_state(color);
+        let turned = !self.state.is_over();
+        if turned {
+            self.players.turn();
         }
-        let player = self.players.current();
-        self.board.apply(moves, player)?;
-        self.update_state();
-        if !self.state.is_over() {
+        Ok(GameUndo {
+            board,
+            state,
+            turned,
+        })
+    }
+    /// Takes back a move played with [`Game::make`], restoring the board, the turn order
+    /// and the state of the game. Moves made afterwards must have been taken back first.
+    pub fn unmake(&mut self, undo: GameUndo) {
+        self.board.unmake(undo.board);
+        if undo.turned {
             self.players.turn();
         }
+        self.state = undo.state;
+    }
+    fn apply(&mut self, moves: Moves) -> Result<(), MoveApplyError> {
+        let undo = self.make(moves)?;
+        self.history.push(Played { undo });
+        Ok(())
+    }
+    pub fn play(&mut self, moves: Moves) -> Result<(), MoveApplyError> {
+        self.apply(moves)?;
+        self.undone.clear();
         Ok(())
     }
+    /// Every move applied so far, in the order they were played.
+    pub fn history(&self) -> &[Played] {
+        &self.history
+    }
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+    /// Takes back the last move, restoring the board, the pieces and the turn order.
+    /// Returns the move taken back, if any.
+    pub fn undo(&mut self) -> Option<Moves> {
+        let played = self.history.pop()?;
+        self.unmake(played.undo);
+        self.undone.push(played);
+        Some(played.moves())
+    }
+    /// Plays again the last move taken back with [`Game::undo`]. Returns the move replayed,
+    /// if any.
+    pub fn redo(&mut self) -> Option<Moves> {
+        let played = self.undone.pop()?;
+        self.apply(played.moves())
+            .expect("an undone move is valid on the position it was taken back from");
+        Some(played.moves())
+    }
 }
 
 #[cfg(test)]
@@ -111,7 +268,9 @@ mod tests {
                 .symbol(Symbol::O)
                 .unwrap()
                 .totem_pos((2, 3)),
-            Err(MovesBuilderError::InvalidTotemMove)
+            Err(MovesBuilderError::Rule(
+                RuleViolation::InvalidTotemMove { .. }
+            ))
         ));
         assert_eq!(game.current_player().color(), Color::Black);
 
@@ -130,4 +289,188 @@ mod tests {
         valid_play!(game => O; (2,1); (3,1));
         assert_eq!(game.state(), &GameState::BlackWins);
     }
+
+    #[test]
+    fn undo_redo_works() {
+        let mut game = Game::default();
+        let initial = game.board().to_string();
+        assert_eq!(game.undo(), None);
+
+        valid_play!(game => O; (2,3); (1,3));
+        let after_first = game.board().to_string();
+        valid_play!(game => X; (2,1); (2,2));
+        assert_eq!(game.history().len(), 2);
+
+        let second = game.undo().unwrap();
+        assert_eq!(game.board().to_string(), after_first);
+        assert_eq!(game.current_player().color(), Color::Black);
+        assert_eq!(game.board().pieces().get(Symbol::X, Color::Black), 8);
+
+        game.undo().unwrap();
+        assert_eq!(game.board().to_string(), initial);
+        assert_eq!(game.current_player().color(), Color::Pink);
+        assert!(!game.can_undo());
+
+        game.redo().unwrap();
+        assert_eq!(game.redo(), Some(second));
+        assert_eq!(game.current_player().color(), Color::Pink);
+        assert_eq!(game.board().pieces().get(Symbol::X, Color::Black), 7);
+        assert!(!game.can_redo());
+
+        game.undo().unwrap();
+        valid_play!(game => O; (3,3); (3,4));
+        assert!(!game.can_redo());
+    }
+
+    #[test]
+    fn undo_after_win_works() {
+        let mut game = Game::default();
+        valid_play!(game => O; (2,3); (1,3));
+        valid_play!(game => O; (3,3); (3,2));
+        valid_play!(game => O; (2,3); (2,4));
+        valid_play!(game => O; (3,3); (3,4));
+        valid_play!(game => O; (2,3); (3,3));
+        valid_play!(game => O; (2,1); (3,1));
+        assert_eq!(game.state(), &GameState::BlackWins);
+        let line = game.winning_line().unwrap();
+        assert_eq!(line.kind(), LineKind::Symbol);
+        assert!(
+            line.contains(Position::new(3, 1).unwrap())
+                && line.contains(Position::new(3, 4).unwrap())
+        );
+        let last = game.history().last().unwrap().moves();
+        assert_eq!(game.play(last), Err(MoveApplyError::GameOver));
+
+        game.undo().unwrap();
+        assert_eq!(game.state(), &GameState::Started);
+        assert_eq!(game.winning_line(), None);
+        assert_eq!(game.current_player().color(), Color::Black);
+        game.redo().unwrap();
+        assert_eq!(game.state(), &GameState::BlackWins);
+    }
+
+    #[test]
+    fn make_unmake_restores_everything() {
+        let mut game = Game::from_fen("POPOPO3/6/2TX3/3TO2/6/BXBXBX3 8,5/5,8 p").unwrap();
+        let fen = game.fen();
+        let hash = game.board().hash();
+        let moves = game.legal_moves().collect::<Vec<_>>();
+        for m in moves {
+            let undo = game.make(m).unwrap();
+            if !game.state().is_over() {
+                let replies = game.legal_moves().collect::<Vec<_>>();
+                for r in replies {
+                    let undo = game.make(r).unwrap();
+                    game.unmake(undo);
+                }
+            }
+            game.unmake(undo);
+            assert_eq!(game.fen(), fen);
+            assert_eq!(game.board().hash(), hash);
+            assert_eq!(game.state(), &GameState::Started);
+            assert!(game.history().is_empty());
+        }
+    }
+
+    #[test]
+    fn fen_round_trips() {
+        let mut game = Game::default();
+        assert_eq!(game.fen(), "6/6/2TX3/3TO2/6/6 8,8/8,8 p");
+        valid_play!(game => O; (2,3); (1,3));
+        valid_play!(game => X; (2,1); (2,2));
+        assert_eq!(game.fen(), "6/2TX3/2BX3/1POTO3/6/6 8,7/7,8 p");
+
+        let parsed = Game::from_fen(&game.fen()).unwrap();
+        assert_eq!(parsed.board().to_string(), game.board().to_string());
+        assert_eq!(parsed.current_player().color(), Color::Pink);
+
+        let parsed = Game::from_fen("PXPXPX3/6/2TX3/3TO2/6/BXBX4 5,8/6,8 b").unwrap();
+        assert_eq!(parsed.current_player().color(), Color::Black);
+        assert_eq!(
+            parsed.board().get(Position::new(1, 5).unwrap()),
+            &Square::Piece(Symbol::X, Color::Black)
+        );
+        assert_eq!(parsed.fen(), "PXPXPX3/6/2TX3/3TO2/6/BXBX4 5,8/6,8 b");
+    }
+
+    #[test]
+    fn fen_errors() {
+        let err = |fen| Game::from_fen(fen).err();
+        assert_eq!(
+            err("6/6/2TX3/3TO2/6/6 8,8/8,8"),
+            Some(FenError::WrongFieldCount)
+        );
+        assert_eq!(
+            err("6/6/2TX3/3TO2/6 8,8/8,8 p"),
+            Some(FenError::WrongFieldCount)
+        );
+        assert_eq!(
+            err("6/6/2TX3/3TO2/6/6 8,8/8,8 x"),
+            Some(FenError::InvalidSide)
+        );
+        assert_eq!(
+            err("6/6/2TX4/3TO2/6/6 8,8/8,8 p"),
+            Some(FenError::InvalidRow(2))
+        );
+        assert_eq!(
+            err("6/6/2TZ3/3TO2/6/6 8,8/8,8 p"),
+            Some(FenError::InvalidRow(2))
+        );
+        assert_eq!(
+            err("6/6/2TX3/3TO2/6/6 8,9/8,8 p"),
+            Some(FenError::InvalidPiecesCount)
+        );
+        assert_eq!(
+            err("6/6/2TX3/3TO2/6/6 8,8/8 p"),
+            Some(FenError::InvalidPiecesCount)
+        );
+        assert_eq!(
+            err("6/6/2TX3/3TX2/6/6 8,8/8,8 p"),
+            Some(FenError::TotemCount(Symbol::X))
+        );
+        assert_eq!(
+            err("PO5/6/2TX3/3TO2/6/6 8,8/8,8 p"),
+            Some(FenError::PiecesMismatch(Symbol::O, Color::Pink))
+        );
+    }
+
+    #[test]
+    fn legal_moves_initial_position() {
+        let game = Game::default();
+        let legal = game.legal_moves().collect::<Vec<_>>();
+        assert_eq!(legal.len(), 68);
+
+        // The X totem slides along its row and column, stopped by the O totem, then a piece
+        // goes on a free square next to it
+        let mut counts = std::collections::BTreeMap::new();
+        for m in legal.iter().filter(|m| m.symbol() == Symbol::X) {
+            let totem = m.totem_new_pos();
+            *counts.entry((totem.x(), totem.y())).or_insert(0) += 1;
+        }
+        assert_eq!(
+            counts.into_iter().collect::<Vec<_>>(),
+            [
+                ((0, 2), 3),
+                ((1, 2), 4),
+                ((2, 0), 3),
+                ((2, 1), 4),
+                ((2, 3), 3),
+                ((2, 4), 4),
+                ((2, 5), 3),
+                ((3, 2), 3),
+                ((4, 2), 4),
+                ((5, 2), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_legal_moves_once_over() {
+        let mut game = Game::from_fen("POPOPO3/6/2TX3/3TO2/6/BXBXBX3 8,5/5,8 p").unwrap();
+        assert!(game.legal_moves().count() > 0);
+        game.play(game.moves_builder().parse("O3,1-3,0").unwrap())
+            .unwrap();
+        assert_eq!(game.state(), &GameState::PinkWins);
+        assert_eq!(game.legal_moves().count(), 0);
+    }
 }
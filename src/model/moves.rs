@@ -1,6 +1,9 @@
 use core::marker::PhantomData;
 
-use super::{board::Square, Board, Player, Position, Symbol};
+use super::{
+    board::Square, position::ParsePositionError, Board, OutOfBoard, Player, Position,
+    RuleViolation, Symbol,
+};
 
 pub(crate) struct MovesData {
     pub symbol: Symbol,
@@ -9,6 +12,8 @@ pub(crate) struct MovesData {
     pub piece_pos: Position,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Moves {
     symbol: Symbol,
     totem_old_pos: Position,
@@ -30,7 +35,43 @@ impl Moves {
         }
     }
 
-    pub fn into_data(self) -> MovesData {
+    pub(crate) fn new(
+        symbol: Symbol,
+        totem_old_pos: Position,
+        totem_new_pos: Position,
+        piece_pos: Position,
+    ) -> Self {
+        Self {
+            symbol,
+            totem_old_pos,
+            totem_new_pos,
+            piece_pos,
+        }
+    }
+
+    pub fn symbol(&self) -> Symbol {
+        self.symbol
+    }
+    pub fn totem_old_pos(&self) -> Position {
+        self.totem_old_pos
+    }
+    pub fn totem_new_pos(&self) -> Position {
+        self.totem_new_pos
+    }
+    pub fn piece_pos(&self) -> Position {
+        self.piece_pos
+    }
+
+    /// The textual form of this move, see [`MovesNotation`].
+    pub fn notation(&self) -> MovesNotation {
+        MovesNotation {
+            symbol: self.symbol,
+            totem_new_pos: self.totem_new_pos,
+            piece_pos: self.piece_pos,
+        }
+    }
+
+    pub(crate) fn into_data(self) -> MovesData {
         let Self {
             symbol,
             totem_old_pos,
@@ -46,13 +87,135 @@ impl Moves {
     }
 }
 
-#[derive(Debug)]
+impl core::fmt::Display for Moves {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.notation().fmt(f)
+    }
+}
+
+/// A move written in Oxono notation: the symbol of the moved totem, its destination and
+/// the square where the piece is placed, positions being written `x,y` like on the board.
+///
+/// For instance `O2,3-1,3` moves the O totem to (2,3) and places an O piece on (1,3). The
+/// symbol may be lowercase and surrounding whitespaces are ignored.
+///
+/// The notation does not tell where the totem comes from, so it has to be checked against
+/// a board with [`MovesNotation::validate`] (or [`MovesBuilder::parse`]) to get a [`Moves`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MovesNotation {
+    symbol: Symbol,
+    totem_new_pos: Position,
+    piece_pos: Position,
+}
+impl MovesNotation {
+    pub fn symbol(&self) -> Symbol {
+        self.symbol
+    }
+    pub fn totem_new_pos(&self) -> Position {
+        self.totem_new_pos
+    }
+    pub fn piece_pos(&self) -> Position {
+        self.piece_pos
+    }
+    /// Checks this move against the given board for the given player, the same way
+    /// [`Moves::builder`] does.
+    pub fn validate(self, board: &Board, player: &Player) -> Result<Moves, MovesBuilderError> {
+        Moves::builder(board, player)
+            .symbol(self.symbol)?
+            .totem_pos(self.totem_new_pos)?
+            .piece_pos(self.piece_pos)
+    }
+}
+impl core::fmt::Display for MovesNotation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let symbol = match self.symbol {
+            Symbol::X => 'X',
+            Symbol::O => 'O',
+        };
+        write!(f, "{symbol}{}-{}", self.totem_new_pos, self.piece_pos)
+    }
+}
+impl core::str::FromStr for MovesNotation {
+    type Err = MovesBuilderError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || MovesBuilderError::InvalidNotation(s.to_string());
+        let mut chars = s.chars();
+        let symbol = match chars.next() {
+            Some('X' | 'x') => Symbol::X,
+            Some('O' | 'o') => Symbol::O,
+            _ => return Err(invalid()),
+        };
+        let (totem, piece) = chars.as_str().split_once('-').ok_or_else(invalid)?;
+        let parse_pos = |s: &str| {
+            s.parse().map_err(|err| match err {
+                ParsePositionError::Invalid(_) => invalid(),
+                ParsePositionError::OutOfBoard(err) => MovesBuilderError::OutOfBoard(err),
+            })
+        };
+        Ok(Self {
+            symbol,
+            totem_new_pos: parse_pos(totem)?,
+            piece_pos: parse_pos(piece)?,
+        })
+    }
+}
+
+/// Reasons for a [`MovesBuilder`] to refuse a step of the move.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MovesBuilderError {
-    TotemNotFound,
-    OutOfBoard,
-    NoPieceLeft,
-    InvalidTotemMove,
-    InvalidPiecePlacement,
+    /// The text isn't written in [`MovesNotation`].
+    InvalidNotation(String),
+    /// There is no totem of this symbol on the board.
+    TotemNotFound(Symbol),
+    /// The text isn't a position written `x,y`.
+    InvalidPosition(String),
+    /// A position is outside of the board.
+    OutOfBoard(OutOfBoard),
+    /// The move breaks the rules.
+    Rule(RuleViolation),
+}
+impl core::fmt::Display for MovesBuilderError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            MovesBuilderError::InvalidNotation(s) => {
+                write!(f, "\"{s}\" isn't a move, write it like O2,3-1,3")
+            }
+            MovesBuilderError::InvalidPosition(s) => ParsePositionError::Invalid(s.clone()).fmt(f),
+            MovesBuilderError::TotemNotFound(symbol) => {
+                write!(f, "there is no {symbol} totem on the board")
+            }
+            MovesBuilderError::OutOfBoard(err) => err.fmt(f),
+            MovesBuilderError::Rule(violation) => violation.fmt(f),
+        }
+    }
+}
+impl std::error::Error for MovesBuilderError {}
+impl From<ParsePositionError> for MovesBuilderError {
+    fn from(err: ParsePositionError) -> Self {
+        match err {
+            ParsePositionError::Invalid(s) => MovesBuilderError::InvalidPosition(s),
+            ParsePositionError::OutOfBoard(err) => MovesBuilderError::OutOfBoard(err),
+        }
+    }
+}
+impl From<OutOfBoard> for MovesBuilderError {
+    fn from(err: OutOfBoard) -> Self {
+        MovesBuilderError::OutOfBoard(err)
+    }
+}
+/// For the positions given to the builder as is.
+impl From<core::convert::Infallible> for MovesBuilderError {
+    fn from(err: core::convert::Infallible) -> Self {
+        match err {}
+    }
+}
+impl From<RuleViolation> for MovesBuilderError {
+    fn from(violation: RuleViolation) -> Self {
+        MovesBuilderError::Rule(violation)
+    }
 }
 
 pub enum MovesBuilderStateData {
@@ -85,17 +248,28 @@ pub struct MovesBuilder<'b, 'p, T: sealed::MoveBuilderState + 'static> {
 pub type MovesBuilderInit<'b, 'p> = MovesBuilder<'b, 'p, MovesBuilderStateInit>;
 
 impl<'b, 'p> MovesBuilder<'b, 'p, MovesBuilderStateInit> {
+    /// Builds a move from its textual form, see [`MovesNotation`].
+    pub fn parse(self, s: &str) -> Result<Moves, MovesBuilderError> {
+        let notation: MovesNotation = s.parse()?;
+        notation.validate(self.board, self.player)
+    }
     pub fn symbol(
         self,
         symbol: Symbol,
     ) -> Result<MovesBuilder<'b, 'p, MovesBuilderStateSymbol>, MovesBuilderError> {
         let Self { board, player, .. } = self;
-        if !board.has_left_piece(symbol, player.color()) {
-            return Err(MovesBuilderError::NoPieceLeft);
+        let color = player.color();
+        if !board.has_left_piece(symbol, color) {
+            return Err(RuleViolation::NoPieceLeft {
+                symbol,
+                color,
+                other_left: board.pieces().get(symbol.opposite(), color),
+            }
+            .into());
         }
         let totem_old_pos = board
             .find(Square::Totem(symbol))
-            .ok_or(MovesBuilderError::TotemNotFound)?;
+            .ok_or(MovesBuilderError::TotemNotFound(symbol))?;
 
         Ok(MovesBuilder {
             board,
@@ -112,11 +286,9 @@ impl<'b, 'p> MovesBuilder<'b, 'p, MovesBuilderStateInit> {
 impl<'b, 'p> MovesBuilder<'b, 'p, MovesBuilderStateSymbol> {
     pub fn totem_pos(
         self,
-        totem_new_pos: impl TryInto<Position>,
+        totem_new_pos: impl TryInto<Position, Error: Into<MovesBuilderError>>,
     ) -> Result<MovesBuilder<'b, 'p, MovesBuilderStateTotemPos>, MovesBuilderError> {
-        let totem_new_pos = totem_new_pos
-            .try_into()
-            .map_err(|_| MovesBuilderError::OutOfBoard)?;
+        let totem_new_pos = totem_new_pos.try_into().map_err(Into::into)?;
         let Self {
             board,
             player,
@@ -135,7 +307,13 @@ impl<'b, 'p> MovesBuilder<'b, 'p, MovesBuilderStateSymbol> {
             .totem_valid_moves(totem_old_pos)
             .all(|p| p != totem_new_pos)
         {
-            return Err(MovesBuilderError::InvalidTotemMove);
+            return Err(RuleViolation::InvalidTotemMove {
+                symbol,
+                from: totem_old_pos,
+                to: totem_new_pos,
+                allowed: board.totem_valid_moves(totem_old_pos).collect(),
+            }
+            .into());
         }
 
         Ok(MovesBuilder {
@@ -152,10 +330,11 @@ impl<'b, 'p> MovesBuilder<'b, 'p, MovesBuilderStateSymbol> {
 }
 
 impl<'b, 'p> MovesBuilder<'b, 'p, MovesBuilderStateTotemPos> {
-    pub fn piece_pos(self, piece_pos: impl TryInto<Position>) -> Result<Moves, MovesBuilderError> {
-        let piece_pos = piece_pos
-            .try_into()
-            .map_err(|_| MovesBuilderError::OutOfBoard)?;
+    pub fn piece_pos(
+        self,
+        piece_pos: impl TryInto<Position, Error: Into<MovesBuilderError>>,
+    ) -> Result<Moves, MovesBuilderError> {
+        let piece_pos = piece_pos.try_into().map_err(Into::into)?;
         let Self {
             board,
             data:
@@ -174,7 +353,13 @@ impl<'b, 'p> MovesBuilder<'b, 'p, MovesBuilderStateTotemPos> {
             .piece_valid_moves(totem_new_pos, symbol)
             .all(|p| p != piece_pos)
         {
-            return Err(MovesBuilderError::InvalidPiecePlacement);
+            return Err(RuleViolation::InvalidPiecePlacement {
+                symbol,
+                pos: piece_pos,
+                totem: totem_new_pos,
+                allowed: board.piece_valid_moves(totem_new_pos, symbol).collect(),
+            }
+            .into());
         }
 
         Ok(Moves {
@@ -185,3 +370,69 @@ impl<'b, 'p> MovesBuilder<'b, 'p, MovesBuilderStateTotemPos> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notation_round_trips() {
+        let board = Board::default();
+        let player = crate::model::Players::default();
+        let moves = Moves::builder(&board, player.current())
+            .parse("o2,3-1,3")
+            .unwrap();
+        assert_eq!(moves.symbol(), Symbol::O);
+        assert_eq!(moves.totem_old_pos(), Position::new(3, 3).unwrap());
+        assert_eq!(moves.to_string(), "O2,3-1,3");
+        assert_eq!(
+            Moves::builder(&board, player.current())
+                .parse(&moves.to_string())
+                .unwrap(),
+            moves
+        );
+    }
+
+    #[test]
+    fn notation_errors_match_builder() {
+        let board = Board::default();
+        let player = crate::model::Players::default();
+        let parse = |s| Moves::builder(&board, player.current()).parse(s);
+        assert!(matches!(
+            parse("O2,3"),
+            Err(MovesBuilderError::InvalidNotation(_))
+        ));
+        assert!(matches!(
+            parse("Z2,3-1,3"),
+            Err(MovesBuilderError::InvalidNotation(_))
+        ));
+        assert!(matches!(
+            parse("O2,a-1,3"),
+            Err(MovesBuilderError::InvalidNotation(_))
+        ));
+        assert!(matches!(
+            parse("O2,3-1,6"),
+            Err(MovesBuilderError::OutOfBoard(OutOfBoard { x: 1, y: 6 }))
+        ));
+        assert!(matches!(
+            parse("O0,0-1,0"),
+            Err(MovesBuilderError::Rule(
+                RuleViolation::InvalidTotemMove { .. }
+            ))
+        ));
+        assert!(matches!(
+            parse("O2,3-5,5"),
+            Err(MovesBuilderError::Rule(
+                RuleViolation::InvalidPiecePlacement { .. }
+            ))
+        ));
+        assert_eq!(
+            parse("O0,0-1,0").unwrap_err().to_string(),
+            "the O totem on 3,3 can't move to 0,0, \
+            it can go to 3,0 3,1 3,2 0,3 1,3 2,3 4,3 5,3 3,4 or 3,5"
+        );
+        // `Error` holds the violation itself rather than the builder error
+        let err = crate::model::Error::from(parse("O0,0-1,0").unwrap_err());
+        assert!(matches!(err, crate::model::Error::Rule(_)));
+    }
+}
@@ -0,0 +1,102 @@
+use super::{Game, Moves};
+
+impl Game {
+    /// Counts the positions reached after playing every sequence of `depth` legal moves
+    /// from the current position, the standard way to check a move generator. Games ending
+    /// before `depth` moves don't count.
+    pub fn perft(&mut self, depth: u32) -> u64 {
+        match depth {
+            0 => 1,
+            1 => self.legal_moves().count() as u64,
+            _ => {
+                let moves = self.legal_moves().collect::<Vec<_>>();
+                moves
+                    .into_iter()
+                    .map(|m| {
+                        let undo = self.make(m).expect("legal moves can be played");
+                        let count = self.perft(depth - 1);
+                        self.unmake(undo);
+                        count
+                    })
+                    .sum()
+            }
+        }
+    }
+    /// Splits [`Game::perft`] by the first move played: returns each legal move with the
+    /// number of positions reached after it.
+    ///
+    /// At depth 0 no move is played, so the list is empty.
+    pub fn perft_divide(&mut self, depth: u32) -> Vec<(Moves, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+        let moves = self.legal_moves().collect::<Vec<_>>();
+        moves
+            .into_iter()
+            .map(|m| {
+                let undo = self.make(m).expect("legal moves can be played");
+                let count = self.perft(depth - 1);
+                self.unmake(undo);
+                (m, count)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_perft(fen: &str, expected: &[u64]) {
+        let mut game = Game::from_fen(fen).unwrap();
+        for (depth, expected) in expected.iter().enumerate() {
+            assert_eq!(
+                game.perft(depth as u32),
+                *expected,
+                "depth {depth} of {fen}"
+            );
+        }
+        assert_eq!(game.fen(), fen);
+    }
+
+    #[test]
+    fn perft_initial_position() {
+        assert_perft(&Game::default().fen(), &[1, 68, 3612, 162680]);
+    }
+
+    #[test]
+    fn perft_middle_game() {
+        assert_perft(
+            "1BOPXPO2/BOTO2PX1/3BXBO1/5BX/1PXBX3/1POTX1PO1 5,5/5,5 p",
+            &[1, 13, 139, 1435, 13958],
+        );
+    }
+
+    #[test]
+    fn perft_enclosed_totem() {
+        assert_perft(
+            "6/1PX4/PXTOBO3/1BX4/3TX2/6 6,8/7,7 p",
+            &[1, 42, 1172, 30235],
+        );
+    }
+
+    #[test]
+    fn perft_stops_at_wins() {
+        assert_perft(
+            "POPOPO3/6/2TX3/3TO2/6/BXBXBX3 8,5/5,8 p",
+            &[1, 58, 2514, 90269],
+        );
+    }
+
+    #[test]
+    fn divide_sums_to_perft() {
+        let mut game = Game::default();
+        let divide = game.perft_divide(3);
+        assert_eq!(divide.len(), 68);
+        assert_eq!(
+            divide.iter().map(|(_, count)| count).sum::<u64>(),
+            game.perft(3)
+        );
+        assert!(game.perft_divide(0).is_empty());
+    }
+}
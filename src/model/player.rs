@@ -20,7 +20,7 @@ impl Player {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct Players {
     players: [Player; 2],
 }
@@ -1,27 +1,98 @@
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(
+        into = "super::serialize::PositionData",
+        try_from = "super::serialize::PositionData"
+    )
+)]
 pub struct Position {
     x: u8,
     y: u8,
 }
 
+/// Coordinates outside of the board, returned when creating a [`Position`] from them.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutOfBoard {
+    pub x: usize,
+    pub y: usize,
+}
+impl core::fmt::Display for OutOfBoard {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{},{} is outside of the board, positions go from 0,0 to 5,5",
+            self.x, self.y
+        )
+    }
+}
+impl std::error::Error for OutOfBoard {}
+
+/// Reasons for a text not to be read as a [`Position`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParsePositionError {
+    /// The text isn't two numbers written `x,y`.
+    Invalid(String),
+    /// The coordinates are outside of the board.
+    OutOfBoard(OutOfBoard),
+}
+impl core::fmt::Display for ParsePositionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ParsePositionError::Invalid(s) => {
+                write!(f, "\"{s}\" isn't a position, write it like 2,3")
+            }
+            ParsePositionError::OutOfBoard(err) => err.fmt(f),
+        }
+    }
+}
+impl std::error::Error for ParsePositionError {}
+
 impl TryFrom<(u8, u8)> for Position {
-    type Error = (u8, u8);
+    type Error = OutOfBoard;
 
     fn try_from((x, y): (u8, u8)) -> Result<Self, Self::Error> {
-        if x > 5 || y > 5 {
-            Err((x, y))
-        } else {
-            Ok(Position { x, y })
-        }
+        Self::checked(x as usize, y as usize)
+    }
+}
+
+/// Reads a position written `x,y`, like its `Display` does.
+impl core::str::FromStr for Position {
+    type Err = ParsePositionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || ParsePositionError::Invalid(s.to_string());
+        let (x, y) = s.split_once(',').ok_or_else(invalid)?;
+        let (Ok(x), Ok(y)) = (x.trim().parse(), y.trim().parse()) else {
+            return Err(invalid());
+        };
+        Self::checked(x, y).map_err(ParsePositionError::OutOfBoard)
+    }
+}
+
+impl core::fmt::Display for Position {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
     }
 }
 
 impl Position {
-    pub fn new(x: u8, y: u8) -> Self {
+    pub fn new(x: u8, y: u8) -> Result<Self, OutOfBoard> {
+        (x, y).try_into()
+    }
+    fn checked(x: usize, y: usize) -> Result<Self, OutOfBoard> {
         if x > 5 || y > 5 {
-            panic!("Invalid position: ({x}, {y})");
+            Err(OutOfBoard { x, y })
+        } else {
+            Ok(Position {
+                x: x as u8,
+                y: y as u8,
+            })
         }
-        Self { x, y }
     }
     pub fn x(&self) -> usize {
         self.x as usize
@@ -100,16 +171,21 @@ impl Position {
         })
     }
 
+    /// Iterates over the 36 positions of the board, row by row.
+    pub fn all() -> impl Iterator<Item = Position> {
+        (0..6).flat_map(|y| (0..6).map(move |x| Position { x, y }))
+    }
+
     pub fn four_latteral_groups() -> impl Iterator<Item = [Position; 4]> {
         let mut i = 0;
         core::iter::from_fn(move || {
             let n = i / 3; // row num
             let f = i % 3; // 3 fours in a row
             i += 1;
-            let mut array = [Position::new(0, 0); 4];
+            let mut array = [Position { x: 0, y: 0 }; 4];
             match n {
                 0..6 => {
-                    let first = Position::new(n, f);
+                    let first = Position { x: n, y: f };
                     for (i, p) in core::iter::once(first)
                         .chain(first.iter_down())
                         .take(4)
@@ -120,7 +196,7 @@ impl Position {
                     Some(array)
                 }
                 6..12 => {
-                    let first = Position::new(f, n - 6);
+                    let first = Position { x: f, y: n - 6 };
                     for (i, p) in core::iter::once(first)
                         .chain(first.iter_right())
                         .take(4)
@@ -145,7 +221,29 @@ mod tests {
         let list = Position::four_latteral_groups().collect::<Vec<_>>();
         assert_eq!(list.len(), 36);
 
-        let p = Position::new;
+        let p = |x, y| Position::new(x, y).unwrap();
         assert_eq!(list[0], [p(0, 0), p(0, 1), p(0, 2), p(0, 3)]);
     }
+
+    #[test]
+    fn positions_parse() {
+        assert_eq!(" 2, 3".parse(), Ok(Position::new(2, 3).unwrap()));
+        assert_eq!(
+            "6,0".parse::<Position>(),
+            Err(ParsePositionError::OutOfBoard(OutOfBoard { x: 6, y: 0 }))
+        );
+        let err = "300,0".parse::<Position>().unwrap_err();
+        assert_eq!(
+            err,
+            ParsePositionError::OutOfBoard(OutOfBoard { x: 300, y: 0 })
+        );
+        assert_eq!(
+            err.to_string(),
+            "300,0 is outside of the board, positions go from 0,0 to 5,5"
+        );
+        assert_eq!(
+            "2;3".parse::<Position>(),
+            Err(ParsePositionError::Invalid("2;3".to_string()))
+        );
+    }
 }
@@ -0,0 +1,306 @@
+use {
+    super::{Color, FenError, Game, GameState, MoveApplyError, MovesBuilderError},
+    std::{fs, io, path::Path},
+};
+
+/// Errors met while reading a game record, see [`Game::from_record`].
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RecordError {
+    /// Serialized as its message with the `serde` feature.
+    #[cfg_attr(feature = "serde", serde(with = "super::serialize::io_error"))]
+    Io(io::Error),
+    /// The header line with the given number (from 1) isn't a `[Name "value"]` tag.
+    InvalidTag(usize),
+    /// The `Setup` tag doesn't hold a valid position.
+    InvalidSetup(FenError),
+    /// The move `notation`, played by `color` at the given move number, is not valid.
+    InvalidMove {
+        number: usize,
+        color: Color,
+        notation: String,
+        error: MovesBuilderError,
+    },
+    /// The move `notation`, at the given move number, can't be applied on the board.
+    UnappliableMove {
+        number: usize,
+        color: Color,
+        notation: String,
+        error: MoveApplyError,
+    },
+    /// The `token`, near the given move number, is neither a move number, a move nor the
+    /// result closing the record.
+    UnexpectedToken { number: usize, token: String },
+    /// The move `notation`, at the given move number, is played after the end of the game.
+    MoveAfterEnd { number: usize, notation: String },
+    /// The `Result` tag doesn't match the outcome of the moves.
+    ResultMismatch { expected: String, found: String },
+}
+impl core::fmt::Display for RecordError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RecordError::Io(err) => write!(f, "cannot access the game record: {err}"),
+            RecordError::InvalidTag(line) => write!(f, "line {line}: invalid header tag"),
+            RecordError::InvalidSetup(err) => write!(f, "invalid setup position: {err}"),
+            RecordError::InvalidMove {
+                number,
+                color,
+                notation,
+                error,
+            } => write!(f, "move {number} ({color}) {notation}: {error}"),
+            RecordError::UnappliableMove {
+                number,
+                color,
+                notation,
+                error,
+            } => write!(f, "move {number} ({color}) {notation}: {error}"),
+            RecordError::UnexpectedToken { number, token } => {
+                write!(f, "move {number}: unexpected `{token}`")
+            }
+            RecordError::MoveAfterEnd { number, notation } => {
+                write!(f, "move {number} {notation}: the game is already over")
+            }
+            RecordError::ResultMismatch { expected, found } => {
+                write!(f, "the moves lead to {found}, not {expected}")
+            }
+        }
+    }
+}
+/// The message of the inner error is part of the `Display` of `RecordError`, so the source
+/// is the inner error's own, not to be written twice.
+impl std::error::Error for RecordError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RecordError::Io(err) => err.source(),
+            RecordError::InvalidSetup(err) => err.source(),
+            RecordError::InvalidMove { error, .. } => error.source(),
+            RecordError::UnappliableMove { error, .. } => error.source(),
+            RecordError::InvalidTag(_)
+            | RecordError::UnexpectedToken { .. }
+            | RecordError::MoveAfterEnd { .. }
+            | RecordError::ResultMismatch { .. } => None,
+        }
+    }
+}
+impl From<io::Error> for RecordError {
+    fn from(err: io::Error) -> Self {
+        RecordError::Io(err)
+    }
+}
+
+fn result_tag(state: &GameState) -> &'static str {
+    match state {
+        GameState::Started => "*",
+        GameState::PinkWins => "1-0",
+        GameState::BlackWins => "0-1",
+        GameState::Draw => "1/2-1/2",
+    }
+}
+
+impl Game {
+    /// Writes this game as a human-readable record: a header of `[Name "value"]` tags
+    /// followed by the list of moves in [`super::MovesNotation`], numbered by pair.
+    ///
+    /// ```text
+    /// [Game "Oxono"]
+    /// [Result "*"]
+    ///
+    /// 1. O2,3-1,3 X2,1-2,2
+    /// 2. O3,3-3,4 *
+    /// ```
+    ///
+    /// A game that didn't start from the initial position gets a `Setup` tag holding its
+    /// starting position in setup notation, see [`Game::from_fen`].
+    pub fn record(&self) -> String {
+        let mut record = String::from("[Game \"Oxono\"]\n");
+        if let Some(setup) = &self.setup {
+            record.push_str(&format!("[Setup \"{setup}\"]\n"));
+        }
+        let result = result_tag(&self.state);
+        record.push_str(&format!("[Result \"{result}\"]\n\n"));
+
+        let mut number = 1;
+        let mut line = String::new();
+        for played in &self.history {
+            if line.is_empty() {
+                line = format!("{number}.");
+                if played.color() == Color::Black {
+                    line.push_str(" ..");
+                }
+            }
+            line.push_str(&format!(" {}", played.moves()));
+            if played.color() == Color::Black {
+                record.push_str(&line);
+                record.push('\n');
+                line.clear();
+                number += 1;
+            }
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        record.push_str(&format!("{line}{result}\n"));
+        record
+    }
+    /// Reads a game record written by [`Game::record`], replaying each move on the board.
+    /// Unknown tags are ignored.
+    pub fn from_record(record: &str) -> Result<Self, RecordError> {
+        let mut lines = record.lines().enumerate();
+        let mut setup = None;
+        let mut expected_result = None;
+        for (i, line) in lines.by_ref() {
+            let line = line.trim();
+            if line.is_empty() {
+                break;
+            }
+            let (name, value) = line
+                .strip_prefix('[')
+                .and_then(|l| l.strip_suffix("\"]"))
+                .and_then(|l| l.split_once(" \""))
+                .ok_or(RecordError::InvalidTag(i + 1))?;
+            match name {
+                "Setup" => setup = Some(value),
+                "Result" => expected_result = Some(value),
+                _ => {}
+            }
+        }
+
+        let mut game = match setup {
+            Some(fen) => Game::from_fen(fen).map_err(RecordError::InvalidSetup)?,
+            None => Game::default(),
+        };
+        let mut number = 1;
+        let mut tokens = lines
+            .flat_map(|(_, line)| line.split_whitespace())
+            .peekable();
+        while let Some(token) = tokens.next() {
+            let unexpected = || RecordError::UnexpectedToken {
+                number,
+                token: token.to_string(),
+            };
+            match token {
+                ".." => continue,
+                "*" | "1-0" | "0-1" | "1/2-1/2" if tokens.peek().is_none() => continue,
+                "*" | "1-0" | "0-1" | "1/2-1/2" => return Err(unexpected()),
+                _ => {}
+            }
+            if let Some(n) = token.strip_suffix('.') {
+                number = n.parse().map_err(|_| unexpected())?;
+                continue;
+            }
+            if game.state().is_over() {
+                return Err(RecordError::MoveAfterEnd {
+                    number,
+                    notation: token.to_string(),
+                });
+            }
+            let color = game.current_player().color();
+            let moves =
+                game.moves_builder()
+                    .parse(token)
+                    .map_err(|error| RecordError::InvalidMove {
+                        number,
+                        color,
+                        notation: token.to_string(),
+                        error,
+                    })?;
+            game.play(moves)
+                .map_err(|error| RecordError::UnappliableMove {
+                    number,
+                    color,
+                    notation: token.to_string(),
+                    error,
+                })?;
+        }
+
+        let found = result_tag(game.state());
+        match expected_result {
+            Some(expected) if expected != found => Err(RecordError::ResultMismatch {
+                expected: expected.to_string(),
+                found: found.to_string(),
+            }),
+            _ => Ok(game),
+        }
+    }
+    /// Saves this game record into a file, see [`Game::record`].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RecordError> {
+        fs::write(path, self.record())?;
+        Ok(())
+    }
+    /// Loads a game record from a file, see [`Game::from_record`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordError> {
+        Self::from_record(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::model::RuleViolation};
+
+    #[test]
+    fn record_round_trips() {
+        let mut game = Game::default();
+        for notation in ["O2,3-1,3", "X2,1-2,2", "O3,3-3,4"] {
+            game.play(game.moves_builder().parse(notation).unwrap())
+                .unwrap();
+        }
+        let record = game.record();
+        assert_eq!(
+            record,
+            "[Game \"Oxono\"]\n[Result \"*\"]\n\n1. O2,3-1,3 X2,1-2,2\n2. O3,3-3,4 *\n"
+        );
+
+        let loaded = Game::from_record(&record).unwrap();
+        assert_eq!(loaded.fen(), game.fen());
+        assert_eq!(loaded.history(), game.history());
+        assert_eq!(loaded.record(), record);
+
+        let mut setup = Game::from_fen("6/2TX3/2BX3/1POTO3/6/6 8,7/7,8 b").unwrap();
+        let record = setup.record();
+        assert!(record.contains("[Setup \"6/2TX3/2BX3/1POTO3/6/6 8,7/7,8 b\"]"));
+        assert_eq!(Game::from_record(&record).unwrap().fen(), setup.fen());
+        let moves = setup.legal_moves().next().unwrap();
+        setup.play(moves).unwrap();
+        let record = setup.record();
+        assert!(record.contains("\n1. .. "));
+        assert_eq!(Game::from_record(&record).unwrap().fen(), setup.fen());
+    }
+
+    #[test]
+    fn record_errors_point_at_move() {
+        let record = "[Game \"Oxono\"]\n\n1. O2,3-1,3 X2,1-2,2\n2. O0,0-1,0\n";
+        assert!(matches!(
+            Game::from_record(record),
+            Err(RecordError::InvalidMove {
+                number: 2,
+                color: Color::Pink,
+                error: MovesBuilderError::Rule(RuleViolation::InvalidTotemMove { .. }),
+                ..
+            })
+        ));
+        // The message of the move error is written once, by the record error
+        let err = Game::from_record(record).unwrap_err();
+        assert!(err.to_string().contains("can't move to 0,0"));
+        assert!(std::error::Error::source(&err).is_none());
+        assert!(matches!(
+            Game::from_record("[Game Oxono]\n\n1. O2,3-1,3\n"),
+            Err(RecordError::InvalidTag(1))
+        ));
+        assert!(matches!(
+            Game::from_record("[Result \"1-0\"]\n\n1. O2,3-1,3\n"),
+            Err(RecordError::ResultMismatch { .. })
+        ));
+        // Only numbers end with a dot, and the result closes the record
+        for (moves, token) in [
+            ("1. O2,3-1,3 garbage. X2,1-2,2", "garbage."),
+            ("1. O2,3-1,3.", "O2,3-1,3."),
+            ("1. O2,3-1,3 * X2,1-2,2", "*"),
+        ] {
+            let err = Game::from_record(&format!("[Game \"Oxono\"]\n\n{moves}\n")).unwrap_err();
+            assert!(
+                matches!(&err, RecordError::UnexpectedToken { number: 1, token: t } if t == token),
+                "{moves}: {err}"
+            );
+        }
+    }
+}
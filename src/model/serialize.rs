@@ -0,0 +1,142 @@
+//! Stable serialized forms of the types whose fields are an implementation detail, used
+//! with the `serde` feature.
+
+use {
+    super::{Board, Color, FenError, Game, GameState, Moves, Pieces, Position, Square},
+    serde::{Deserialize, Serialize},
+};
+
+/// A [`Position`] as its two coordinates, checked when deserialized.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct PositionData {
+    x: u8,
+    y: u8,
+}
+impl From<Position> for PositionData {
+    fn from(pos: Position) -> Self {
+        Self {
+            x: pos.x() as u8,
+            y: pos.y() as u8,
+        }
+    }
+}
+impl TryFrom<PositionData> for Position {
+    type Error = super::OutOfBoard;
+
+    fn try_from(PositionData { x, y }: PositionData) -> Result<Self, Self::Error> {
+        Position::new(x, y)
+    }
+}
+
+/// A [`Board`] as its rows of squares, from top to bottom, the pieces left to the players
+/// and the side to move. The hash is computed again when deserialized.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct BoardData {
+    squares: [[Square; 6]; 6],
+    pieces: Pieces,
+    side_to_move: Color,
+}
+impl From<Board> for BoardData {
+    fn from(board: Board) -> Self {
+        let mut squares = [[Square::Empty; 6]; 6];
+        for pos in Position::all() {
+            squares[pos.y()][pos.x()] = *board.get(pos);
+        }
+        Self {
+            squares,
+            pieces: board.pieces().clone(),
+            side_to_move: board.side_to_move(),
+        }
+    }
+}
+impl TryFrom<BoardData> for Board {
+    type Error = FenError;
+
+    fn try_from(data: BoardData) -> Result<Self, Self::Error> {
+        let mut squares = [Square::Empty; 36];
+        for (i, square) in data.squares.as_flattened().iter().enumerate() {
+            squares[i] = *square;
+        }
+        let mut board = Board::from_squares(squares, data.pieces)?;
+        if data.side_to_move == Color::Black {
+            board.toggle_side();
+        }
+        Ok(board)
+    }
+}
+
+/// A [`Game`] as the moves played from its setup, along with the resulting position for
+/// the consumers that don't replay the moves. The moves are replayed when deserialized and
+/// must lead to the given position.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct GameData {
+    /// The position the game started from, in setup notation, if not the initial one.
+    setup: Option<String>,
+    history: Vec<Moves>,
+    /// The moves taken back, the next one to be played again first.
+    undone: Vec<Moves>,
+    state: GameState,
+    current_player: Color,
+    board: Board,
+}
+impl From<Game> for GameData {
+    fn from(game: Game) -> Self {
+        Self {
+            history: game.history.iter().map(|played| played.moves()).collect(),
+            undone: game
+                .undone
+                .iter()
+                .rev()
+                .map(|played| played.moves())
+                .collect(),
+            state: game.state,
+            current_player: game.current_player().color(),
+            board: game.board,
+            setup: game.setup,
+        }
+    }
+}
+impl TryFrom<GameData> for Game {
+    type Error = String;
+
+    fn try_from(data: GameData) -> Result<Self, Self::Error> {
+        let mut game = match &data.setup {
+            Some(fen) => Game::from_fen(fen).map_err(|err| format!("invalid setup: {err}"))?,
+            None => Game::default(),
+        };
+        for (i, moves) in data.history.iter().chain(&data.undone).enumerate() {
+            game.apply(*moves)
+                .map_err(|err| format!("move {} {moves}: {err}", i + 1))?;
+        }
+        for _ in &data.undone {
+            game.undo();
+        }
+        if game.state != data.state
+            || game.current_player().color() != data.current_player
+            || game.board.fen() != data.board.fen()
+        {
+            return Err("the moves don't lead to the given position".to_string());
+        }
+        Ok(game)
+    }
+}
+
+/// An [`std::io::Error`] as its message, read back as an error of kind `Other`.
+pub(crate) mod io_error {
+    use {
+        serde::{Deserialize, Deserializer, Serializer},
+        std::io,
+    };
+
+    pub(crate) fn serialize<S: Serializer>(
+        err: &io::Error,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(err)
+    }
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<io::Error, D::Error> {
+        String::deserialize(deserializer).map(io::Error::other)
+    }
+}
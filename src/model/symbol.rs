@@ -1,4 +1,5 @@
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Symbol {
     X,
     O,
@@ -12,3 +13,12 @@ impl Symbol {
         }
     }
 }
+
+impl core::fmt::Display for Symbol {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Symbol::X => write!(f, "X"),
+            Symbol::O => write!(f, "O"),
+        }
+    }
+}
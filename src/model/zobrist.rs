@@ -0,0 +1,58 @@
+//! Random keys of the Zobrist hashing of [`super::Board`] positions.
+//!
+//! The hash of a position is the xor of the keys of each non-empty square, of each pieces
+//! counter and of the side to move when it is Black, so that it can be updated
+//! incrementally when a move is applied or taken back.
+
+use super::{board::Square, Color, Symbol};
+
+const fn splitmix64(state: u64) -> (u64, u64) {
+    let state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (state, z ^ (z >> 31))
+}
+
+/// Keys of the 6 kinds of non-empty squares, for each of the 36 squares, followed by the
+/// keys of the 9 possible values of each of the 4 pieces counters and the key of the side.
+const KEYS: [u64; 36 * 6 + 4 * 9 + 1] = {
+    let mut keys = [0; 36 * 6 + 4 * 9 + 1];
+    let mut state = 0x0C0F_FEE0_0DEC_AF00;
+    let mut i = 0;
+    while i < keys.len() {
+        let (next, key) = splitmix64(state);
+        state = next;
+        keys[i] = key;
+        i += 1;
+    }
+    keys
+};
+
+const fn kind(symbol: Symbol, color: Color) -> usize {
+    match (symbol, color) {
+        (Symbol::X, Color::Pink) => 0,
+        (Symbol::X, Color::Black) => 1,
+        (Symbol::O, Color::Pink) => 2,
+        (Symbol::O, Color::Black) => 3,
+    }
+}
+
+/// Key of `square` standing on the square at the given index of the board.
+pub(crate) fn square(index: usize, square: Square) -> u64 {
+    let kind = match square {
+        Square::Empty => return 0,
+        Square::Piece(symbol, color) => kind(symbol, color),
+        Square::Totem(Symbol::X) => 4,
+        Square::Totem(Symbol::O) => 5,
+    };
+    KEYS[index * 6 + kind]
+}
+
+/// Key of the counter of `symbol` pieces of `color` holding `count` pieces.
+pub(crate) fn pieces(symbol: Symbol, color: Color, count: i32) -> u64 {
+    KEYS[36 * 6 + kind(symbol, color) * 9 + count as usize]
+}
+
+/// Key toggled at each move, set when Black is to move.
+pub(crate) const SIDE: u64 = KEYS[36 * 6 + 4 * 9];
@@ -0,0 +1,43 @@
+use {oxono::model::Game, std::time::Instant};
+
+pub const USAGE: &str = "Usage: oxono perft <depth> [fen] [--divide]";
+
+/// Runs the `perft` subcommand: counts the positions reached from the initial position, or
+/// from the given one, after `depth` moves.
+pub fn run(args: impl Iterator<Item = String>) -> Result<(), String> {
+    let (mut depth, mut fen, mut divide) = (None, None, false);
+    for arg in args {
+        match arg.as_str() {
+            "--divide" => divide = true,
+            _ if depth.is_none() => {
+                depth = Some(arg.parse().map_err(|_| "depth must be a number")?);
+            }
+            _ if fen.is_none() => fen = Some(arg),
+            _ => return Err(format!("unknown argument {arg}")),
+        }
+    }
+    let depth = depth.ok_or("missing depth")?;
+    let mut game = match fen {
+        Some(fen) => Game::from_fen(&fen).map_err(|err| format!("invalid position: {err}"))?,
+        None => Game::default(),
+    };
+
+    let start = Instant::now();
+    let nodes = if divide {
+        let divide = game.perft_divide(depth);
+        for (moves, count) in &divide {
+            println!("{moves}: {count}");
+        }
+        println!();
+        divide.iter().map(|(_, count)| count).sum()
+    } else {
+        game.perft(depth)
+    };
+    let elapsed = start.elapsed();
+    println!("Nodes: {nodes}");
+    println!(
+        "Time: {elapsed:.2?} ({:.0} nodes/s)",
+        nodes as f64 / elapsed.as_secs_f64()
+    );
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::model::{Game, GameState};
+use oxono::model::{Color, Error, Game, GameState, LineKind, Moves};
 
 pub struct View;
 impl View {
@@ -8,7 +8,10 @@ impl View {
     }
 
     pub fn display_prompt_symbol(&self) {
-        println!("Enter symbol (o/x) ");
+        println!(
+            "Enter symbol (o/x) or a full move (e.g. O2,3-1,3), \
+            u to undo, r to redo, save/load [file] "
+        );
     }
     pub fn display_prompt_totem(&self) {
         println!("Enter new totem pos (x,y) ");
@@ -17,8 +20,18 @@ impl View {
         println!("Enter piece pos (x,y) ");
     }
 
-    pub fn display_error<E: core::fmt::Debug>(&self, err: E) {
-        println!("Error: {err:?}");
+    pub fn display_computer_thinking(&self, color: Color) {
+        println!("Computer ({color}) is thinking...");
+    }
+    pub fn display_computer_move(&self, color: Color, moves: Moves) {
+        println!("Computer ({color}) plays {moves}");
+    }
+
+    pub fn display_saved(&self, path: &str) {
+        println!("Game saved to {path}");
+    }
+    pub fn display_error(&self, err: impl Into<Error>) {
+        println!("Error: {}", err.into());
     }
 }
 
@@ -36,12 +49,21 @@ impl<'g> core::fmt::Display for GameView<'g> {
             GameState::BlackWins => writeln!(f, "Over. Black wins.")?,
             GameState::Draw => writeln!(f, "Over. Nobody wins.")?,
         }
-        writeln!(
-            f,
-            "Current player: {:?}",
-            self.game.current_player().color()
-        )?;
-        writeln!(f, "\n{}", self.game.board())?;
+        writeln!(f, "Current player: {}", self.game.current_player().color())?;
+        if let Some(played) = self.game.history().last() {
+            writeln!(f, "Last move: {} {}", played.color(), played.moves())?;
+        }
+        if let Some(line) = self.game.winning_line() {
+            let [a, b, c, d] = line.positions();
+            let kind = match line.kind() {
+                LineKind::Color => "color",
+                LineKind::Symbol => "symbol",
+                LineKind::Both => "color and symbol",
+            };
+            writeln!(f, "Winning line: {a} {b} {c} {d} (same {kind})")?;
+        }
+        writeln!(f)?;
+        self.fmt_board(f)?;
         writeln!(f, "==========================")?;
 
         Ok(())
@@ -52,4 +74,17 @@ impl<'g> GameView<'g> {
     pub fn new(game: &'g Game) -> Self {
         Self { game }
     }
+
+    /// Writes the board, the squares of the winning line in reverse video.
+    fn fmt_board(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let line = self.game.winning_line();
+        let board = self.game.board().display_with(|f, pos, square| {
+            if line.is_some_and(|line| line.contains(pos)) {
+                write!(f, "\x1b[7m{square}\x1b[0m")
+            } else {
+                write!(f, "{square}")
+            }
+        });
+        writeln!(f, "{board}")
+    }
 }
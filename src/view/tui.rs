@@ -1,8 +1,6 @@
 use {
-    crate::{
-        controller::UIState,
-        model::{Color, Game, Position, Square, Symbol},
-    },
+    crate::controller::UIState,
+    oxono::model::{Color, Game, LineKind, Position, Square, Symbol},
     ratatui::{
         buffer::Buffer,
         layout::{Constraint, Direction, Flex, Layout, Rect},
@@ -11,7 +9,7 @@ use {
         text::Line,
         widgets::{
             canvas::{self, Canvas},
-            Block, BorderType, Clear, Paragraph, Widget,
+            Block, BorderType, Clear, Paragraph, Widget, Wrap,
         },
         Frame,
     },
@@ -23,7 +21,10 @@ pub fn draw(game: &Game, ui: &UIState, frame: &mut Frame) {
     frame.render_widget(View(game, ui), frame.area());
     if let Some(msg) = ui.error_msg() {
         let block = Block::bordered().title("Error");
-        let paragraph = Paragraph::new(msg).centered().block(block);
+        let paragraph = Paragraph::new(msg)
+            .centered()
+            .wrap(Wrap { trim: true })
+            .block(block);
         let area = popup_area(frame.area(), 40, 20);
         frame.render_widget(Clear, area); //this clears out the background
         frame.render_widget(paragraph, area);
@@ -52,6 +53,19 @@ impl<'g, 'ui> Widget for View<'g, 'ui> {
             "<arrows> ".blue().bold(),
             " Select ".into(),
             "<Enter> ".blue().bold(),
+            " Undo ".into(),
+            "<U> ".blue().bold(),
+            " Redo ".into(),
+            "<R> ".blue().bold(),
+            " Save ".into(),
+            "<S> ".blue().bold(),
+            " Load ".into(),
+            "<L> ".blue().bold(),
+            match self.1.computer() {
+                None => " Computer: off ".into(),
+                Some(color) => format!(" Computer: {color} ").into(),
+            },
+            "<C> ".blue().bold(),
         ]);
         let block = Block::bordered()
             .title(title.centered())
@@ -82,20 +96,34 @@ impl<'g, 'ui> Widget for View<'g, 'ui> {
             .split(h_layout[1]);
 
         block.render(area, buf);
-        Paragraph::new(Line::from(vec![match self.0.state() {
-            crate::model::GameState::Started => "Game started".bold(),
-            crate::model::GameState::PinkWins => {
-                "Game is over: Pink wins.".bold().green().slow_blink()
-            }
-            crate::model::GameState::BlackWins => {
-                "Game is over: Black wins.".bold().green().slow_blink()
-            }
-            crate::model::GameState::Draw => {
+        let line = match self.0.winning_line().map(|line| line.kind()) {
+            Some(LineKind::Color) => " (four of a color)",
+            Some(LineKind::Symbol) => " (four of a symbol)",
+            Some(LineKind::Both) => " (four of a color and symbol)",
+            None => "",
+        };
+        let state = Line::from(vec![match self.0.state() {
+            oxono::model::GameState::Started => "Game started".bold(),
+            oxono::model::GameState::PinkWins => format!("Game is over: Pink wins{line}.")
+                .bold()
+                .green()
+                .slow_blink(),
+            oxono::model::GameState::BlackWins => format!("Game is over: Black wins{line}.")
+                .bold()
+                .green()
+                .slow_blink(),
+            oxono::model::GameState::Draw => {
                 "Game is over: Nobody wins.".bold().green().slow_blink()
             }
-        }]))
-        .centered()
-        .render(v_layout[0], buf);
+        }]);
+        let notice = if self.1.is_thinking() {
+            Line::from("Computer is thinking…".italic().slow_blink())
+        } else {
+            Line::from(self.1.notice().unwrap_or_default().italic())
+        };
+        Paragraph::new(vec![state, notice])
+            .centered()
+            .render(v_layout[0], buf);
         PinkPiecesView(self.0).render(v_layout[1], buf);
         BoardView(self.0, self.1).render(v_layout[2], buf);
         BlackPiecesView(self.0).render(v_layout[3], buf);
@@ -158,6 +186,34 @@ where
             color: style::Color::Green,
         });
     }
+    fn draw_highlight_square(&mut self, pos: Position) {
+        let ctx = &mut self.0;
+        let cx = pos.x() as f64 * 100.0;
+        let cy = (5 - pos.y()) as f64 * 100.0;
+
+        ctx.draw(&canvas::Rectangle {
+            x: cx,
+            y: cy,
+            width: 100.0,
+            height: 100.0,
+            color: style::Color::Yellow,
+        });
+    }
+    fn draw_winning_square(&mut self, pos: Position) {
+        let ctx = &mut self.0;
+        let cx = pos.x() as f64 * 100.0;
+        let cy = (5 - pos.y()) as f64 * 100.0;
+
+        for inset in [0.0, 8.0] {
+            ctx.draw(&canvas::Rectangle {
+                x: cx + inset,
+                y: cy + inset,
+                width: 100.0 - 2.0 * inset,
+                height: 100.0 - 2.0 * inset,
+                color: style::Color::LightGreen,
+            });
+        }
+    }
     fn draw_selection_square(&mut self, color: Color, pos: Position) {
         let ctx = &mut self.0;
         let cx = pos.x() as f64 * 100.0;
@@ -259,29 +315,26 @@ impl<'g, 'ui> Widget for BoardView<'g, 'ui> {
             .paint(|ctx| {
                 let mut d = OxonoDrawer(ctx);
 
-                for y in 0..6 {
-                    for x in 0..6 {
-                        let pos = Position::new(x, y);
-                        d.draw_board_square(pos);
+                for pos in Position::all() {
+                    d.draw_board_square(pos);
 
-                        if Some(pos) == selected_totem_pos {
-                            if let Some(symbol) = selected_symbol {
-                                d.draw_totem(symbol, pos);
-                            }
+                    if Some(pos) == selected_totem_pos {
+                        if let Some(symbol) = selected_symbol {
+                            d.draw_totem(symbol, pos);
                         }
-                        match board.get(pos) {
-                            Square::Totem(symbol)
-                                if Some(symbol) != selected_symbol.as_ref()
-                                    || selected_symbol.is_none()
-                                    || selected_totem_pos.is_none() =>
-                            {
-                                d.draw_totem(*symbol, pos);
-                            }
-                            Square::Piece(symbol, color) => {
-                                d.draw_piece(*symbol, *color, pos);
-                            }
-                            _ => {}
+                    }
+                    match board.get(pos) {
+                        Square::Totem(symbol)
+                            if Some(symbol) != selected_symbol.as_ref()
+                                || selected_symbol.is_none()
+                                || selected_totem_pos.is_none() =>
+                        {
+                            d.draw_totem(*symbol, pos);
+                        }
+                        Square::Piece(symbol, color) => {
+                            d.draw_piece(*symbol, *color, pos);
                         }
+                        _ => {}
                     }
                 }
                 if let Some(s) = selected_symbol {
@@ -305,6 +358,16 @@ impl<'g, 'ui> Widget for BoardView<'g, 'ui> {
                     d.draw_board_square(p);
                 }
 
+                if let Some(moves) = self.1.highlighted_move() {
+                    d.draw_highlight_square(moves.totem_new_pos());
+                    d.draw_highlight_square(moves.piece_pos());
+                }
+                if let Some(line) = self.0.winning_line() {
+                    for p in line.positions() {
+                        d.draw_winning_square(p);
+                    }
+                }
+
                 if let Some(p) = self.1.selected_pos() {
                     d.draw_selection_square(self.0.current_player().color(), p);
                 }
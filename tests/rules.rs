@@ -0,0 +1,82 @@
+//! Conformance of the end of the game to the rules of Oxono, checked on setup positions.
+
+use oxono::model::{Game, GameState};
+
+/// Plays `notation` on the position `fen` and returns the resulting state.
+fn play(fen: &str, notation: &str) -> GameState {
+    let mut game = Game::from_fen(fen).unwrap();
+    let moves = game.moves_builder().parse(notation).unwrap();
+    game.play(moves).unwrap();
+    *game.state()
+}
+
+#[test]
+fn aligning_four_pieces_of_a_color_wins() {
+    let fen = "POPXPO3/6/2TX3/3TO2/6/BXBXBX3 7,6/5,8 p";
+    assert_eq!(play(fen, "O3,1-3,0"), GameState::PinkWins);
+}
+
+#[test]
+fn aligning_four_pieces_of_a_symbol_wins_for_the_player_placing_the_last_one() {
+    let fen = "POPOBO3/6/2TX3/3TO2/6/PX1BXPXBX1 6,6/6,7 b";
+    assert_eq!(play(fen, "O3,1-3,0"), GameState::BlackWins);
+}
+
+#[test]
+fn game_goes_on_without_alignment() {
+    let fen = "POPXPO3/6/2TX3/3TO2/6/BXBXBX3 7,6/5,8 p";
+    assert_eq!(play(fen, "O3,1-4,1"), GameState::Started);
+}
+
+#[test]
+fn winning_with_the_last_piece_wins() {
+    let fen = "1PX1PXPXBX/POBXBOPXBXBX/BXPXBOBXPXPO/PO1BOBOBOPX/POPOTXPXPOPO/TOBOBOPOBXBO \
+               0,0/1,0 b";
+    assert_eq!(play(fen, "X2,0-1,3"), GameState::BlackWins);
+}
+
+#[test]
+fn placing_the_last_piece_without_alignment_draws() {
+    let fen = "1PX1PXPXBX/POBXBOPXBXBX/BXPXBOBXPXPO/PO1BOBOBOPX/POPOTXPXPOPO/TOBOBOPOBXBO \
+               0,0/1,0 b";
+    assert_eq!(play(fen, "X2,0-0,0"), GameState::Draw);
+}
+
+#[test]
+fn player_unable_to_play_draws() {
+    // Black has placed all of its pieces, Pink has two left
+    let fen = "TOBX1BXBXBO/1POBXBXBOPX/BOPOPXBOPXPO/BXTXBOPXBXPX/POBOBO1POBX/POPXPOBOPO1";
+    let game = Game::from_fen(&format!("{fen} 2,0/0,0 b")).unwrap();
+    assert_eq!(game.state(), &GameState::Draw);
+    assert_eq!(game.legal_moves().count(), 0);
+
+    assert_eq!(
+        play(&format!("{fen} 2,0/0,0 p"), "X2,0-5,5"),
+        GameState::Draw
+    );
+    // Winning still comes first
+    assert_eq!(
+        play(&format!("{fen} 2,0/0,0 p"), "X0,1-2,0"),
+        GameState::PinkWins
+    );
+}
+
+#[test]
+fn setup_with_an_alignment_is_won_by_the_last_player() {
+    let game = Game::from_fen("POPOPOPO2/6/2TX3/3TO2/6/BXBXBX3 8,4/5,8 b").unwrap();
+    assert_eq!(game.state(), &GameState::PinkWins);
+    assert_eq!(game.legal_moves().count(), 0);
+}
+
+#[test]
+fn undoing_a_winning_last_piece_resumes_the_game() {
+    let fen = "1PX1PXPXBX/POBXBOPXBXBX/BXPXBOBXPXPO/PO1BOBOBOPX/POPOTXPXPOPO/TOBOBOPOBXBO \
+               0,0/1,0 b";
+    let mut game = Game::from_fen(fen).unwrap();
+    game.play(game.moves_builder().parse("X2,0-1,3").unwrap())
+        .unwrap();
+    assert_eq!(game.state(), &GameState::BlackWins);
+    game.undo().unwrap();
+    assert_eq!(game.state(), &GameState::Started);
+    assert_eq!(game.fen(), fen.replace("               ", ""));
+}
@@ -0,0 +1,141 @@
+//! The JSON shape of the model types, relied upon by other tools consuming games.
+#![cfg(feature = "serde")]
+
+use {
+    oxono::model::{
+        Color, Error, FenError, Game, GameState, MoveApplyError, Position, RecordError, Square,
+        Symbol,
+    },
+    serde_json::json,
+};
+
+fn round_trip<T>(value: &T) -> T
+where
+    T: serde::Serialize + serde::de::DeserializeOwned,
+{
+    serde_json::from_value(serde_json::to_value(value).unwrap()).unwrap()
+}
+
+#[test]
+fn simple_types_shape() {
+    let pos = Position::new(2, 3).unwrap();
+    assert_eq!(
+        serde_json::to_value(pos).unwrap(),
+        json!({ "x": 2, "y": 3 })
+    );
+    assert_eq!(round_trip(&pos), pos);
+    assert!(serde_json::from_value::<Position>(json!({ "x": 6, "y": 0 })).is_err());
+
+    assert_eq!(serde_json::to_value(Symbol::X).unwrap(), json!("X"));
+    assert_eq!(serde_json::to_value(Color::Pink).unwrap(), json!("Pink"));
+    assert_eq!(
+        serde_json::to_value(GameState::BlackWins).unwrap(),
+        json!("BlackWins")
+    );
+    for square in [
+        Square::Empty,
+        Square::Totem(Symbol::O),
+        Square::Piece(Symbol::X, Color::Black),
+    ] {
+        assert_eq!(round_trip(&square), square);
+    }
+    assert_eq!(
+        serde_json::to_value(Square::Piece(Symbol::X, Color::Black)).unwrap(),
+        json!({ "Piece": ["X", "Black"] })
+    );
+    assert_eq!(
+        serde_json::to_value(Square::Totem(Symbol::O)).unwrap(),
+        json!({ "Totem": "O" })
+    );
+    assert_eq!(serde_json::to_value(Square::Empty).unwrap(), json!("Empty"));
+}
+
+#[test]
+fn moves_shape() {
+    let game = Game::default();
+    let moves = game.moves_builder().parse("O2,3-1,3").unwrap();
+    let value = serde_json::to_value(moves).unwrap();
+    assert_eq!(
+        value,
+        json!({
+            "symbol": "O",
+            "totem_old_pos": { "x": 3, "y": 3 },
+            "totem_new_pos": { "x": 2, "y": 3 },
+            "piece_pos": { "x": 1, "y": 3 },
+        })
+    );
+    assert_eq!(round_trip(&moves), moves);
+}
+
+#[test]
+fn board_shape() {
+    let game = Game::from_fen("6/6/2TX3/3TO2/1PO4/6 8,7/8,8 b").unwrap();
+    let value = serde_json::to_value(game.board()).unwrap();
+    assert_eq!(
+        value["pieces"],
+        json!({ "x_pink": 8, "x_black": 8, "o_pink": 7, "o_black": 8 })
+    );
+    assert_eq!(value["squares"][2][2], json!({ "Totem": "X" }));
+    assert_eq!(value["squares"][4][1], json!({ "Piece": ["O", "Pink"] }));
+    assert_eq!(value["squares"][0][0], json!("Empty"));
+    assert_eq!(value["side_to_move"], json!("Black"));
+
+    assert_eq!(&round_trip(game.board()), game.board());
+
+    let mut invalid = value.clone();
+    invalid["squares"][2][2] = json!("Empty");
+    assert!(serde_json::from_value::<oxono::model::Board>(invalid).is_err());
+}
+
+#[test]
+fn game_round_trips() {
+    let mut game = Game::from_fen("6/6/2TX3/3TO2/1PO4/6 8,7/8,8 b").unwrap();
+    for notation in ["X2,1-2,2", "O2,3-3,3", "X1,1-2,1"] {
+        game.play(game.moves_builder().parse(notation).unwrap())
+            .unwrap();
+    }
+    game.undo().unwrap();
+    game.undo().unwrap();
+
+    let value = serde_json::to_value(&game).unwrap();
+    assert_eq!(value["setup"], json!("6/6/2TX3/3TO2/1PO4/6 8,7/8,8 b"));
+    assert_eq!(value["history"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        value["undone"][0]["totem_new_pos"],
+        json!({ "x": 2, "y": 3 })
+    );
+    assert_eq!(value["state"], json!("Started"));
+    assert_eq!(value["current_player"], json!("Pink"));
+
+    let mut copy = round_trip(&game);
+    assert_eq!(copy.fen(), game.fen());
+    assert_eq!(copy.history(), game.history());
+    assert_eq!(copy.redo(), game.redo());
+    assert_eq!(copy.redo(), game.redo());
+    assert_eq!(copy.fen(), game.fen());
+
+    let mut tampered = value.clone();
+    tampered["current_player"] = json!("Black");
+    assert!(serde_json::from_value::<Game>(tampered).is_err());
+}
+
+#[test]
+fn errors_shape() {
+    let err = MoveApplyError::NotEmpty(Position::new(1, 2).unwrap());
+    assert_eq!(
+        serde_json::to_value(&err).unwrap(),
+        json!({ "NotEmpty": { "x": 1, "y": 2 } })
+    );
+    assert_eq!(round_trip(&err), err);
+    let err = FenError::PiecesMismatch(Symbol::O, Color::Pink);
+    assert_eq!(
+        serde_json::to_value(&err).unwrap(),
+        json!({ "PiecesMismatch": ["O", "Pink"] })
+    );
+    let err = Error::from(RecordError::Io(std::io::Error::other("disk full")));
+    assert_eq!(
+        serde_json::to_value(&err).unwrap(),
+        json!({ "Record": { "Io": "disk full" } })
+    );
+    assert_eq!(round_trip(&err).to_string(), err.to_string());
+}